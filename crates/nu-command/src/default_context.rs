@@ -477,6 +477,20 @@ pub fn create_default_context() -> EngineState {
             IsAdmin,
         };
 
+        // IOx
+        bind_command! {
+            Ioxpredicate,
+            IoxCommands,
+            IoxFromLp,
+            IoxToLp,
+            IoxConfig,
+            IoxSession,
+            IoxSessionSet,
+            IoxSessionShow,
+            IoxSessionClear,
+            Ioxtrace,
+        };
+
         // Deprecated
         bind_command! {
             HashBase64,
@@ -5,6 +5,7 @@ use nu_protocol::{
     Category, Example, IntoInterruptiblePipelineData, IntoPipelineData, PipelineData, ShellError,
     Signature, Span, Type, Value,
 };
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Clone)]
 pub struct Length;
@@ -23,8 +24,10 @@ impl Command for Length {
             .input_output_types(vec![
                 (Type::List(Box::new(Type::Any)), Type::Int),
                 (Type::Table(vec![]), Type::Int),
+                (Type::String, Type::Int),
             ])
             .switch("column", "Show the number of columns in a table", Some('c'))
+            .switch("chars", "Show the number of graphemes in a string", None)
             .category(Category::Filters)
     }
 
@@ -40,8 +43,17 @@ impl Command for Length {
         input: PipelineData,
     ) -> Result<nu_protocol::PipelineData, ShellError> {
         let col = call.has_flag("column");
+        let chars = call.has_flag("chars");
+        if col && chars {
+            return Err(ShellError::IncompatibleParametersSingle(
+                "--column and --chars are mutually exclusive".into(),
+                call.head,
+            ));
+        }
         if col {
             length_col(engine_state, call, input)
+        } else if chars {
+            length_chars(call, input)
         } else {
             length_row(call, input)
         }
@@ -59,6 +71,11 @@ impl Command for Length {
                 example: "[{columnA: A0 columnB: B0}] | length -c",
                 result: Some(Value::test_int(2)),
             },
+            Example {
+                description: "Count the graphemes in a string",
+                example: "'nushell' | length --chars",
+                result: Some(Value::test_int(7)),
+            },
         ]
     }
 }
@@ -76,6 +93,19 @@ fn length_col(
     )
 }
 
+fn length_chars(call: &Call, input: PipelineData) -> Result<PipelineData, ShellError> {
+    match input {
+        PipelineData::Value(Value::String { val, .. }, ..) => {
+            let count = val.graphemes(true).count() as i64;
+            Ok(Value::int(count, call.head).into_pipeline_data())
+        }
+        _ => Err(ShellError::TypeMismatch(
+            "--chars only works on strings".into(),
+            call.head,
+        )),
+    }
+}
+
 fn length_row(call: &Call, input: PipelineData) -> Result<PipelineData, ShellError> {
     match input {
         PipelineData::Value(Value::Nothing { .. }, ..) => {
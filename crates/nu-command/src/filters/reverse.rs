@@ -4,6 +4,7 @@ use nu_protocol::{
     Category, Example, IntoInterruptiblePipelineData, PipelineData, ShellError, Signature, Span,
     Type, Value,
 };
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Clone)]
 pub struct Reverse;
@@ -21,12 +22,13 @@ impl Command for Reverse {
                     Type::List(Box::new(Type::Any)),
                 ),
                 (Type::Table(vec![]), Type::Table(vec![])),
+                (Type::String, Type::String),
             ])
             .category(Category::Filters)
     }
 
     fn usage(&self) -> &str {
-        "Reverses the input list or table."
+        "Reverses the input list, table, or string."
     }
 
     fn search_terms(&self) -> Vec<&str> {
@@ -48,6 +50,11 @@ impl Command for Reverse {
                     span: Span::test_data(),
                 }),
             },
+            Example {
+                example: "'nushell' | reverse",
+                description: "Reverse a string",
+                result: Some(Value::test_string("llehsun")),
+            },
             Example {
                 example: "[{a: 1} {a: 2}] | reverse",
                 description: "Reverse a table",
@@ -71,6 +78,19 @@ impl Command for Reverse {
     ) -> Result<PipelineData, ShellError> {
         let metadata = input.metadata();
 
+        // reverse strings by grapheme cluster so multi-byte characters and
+        // combining sequences aren't torn apart
+        if let PipelineData::Value(Value::String { val, span }, ..) = input {
+            let reversed: String = val.graphemes(true).rev().collect();
+            return Ok(PipelineData::Value(
+                Value::String {
+                    val: reversed,
+                    span,
+                },
+                metadata,
+            ));
+        }
+
         #[allow(clippy::needless_collect)]
         let v: Vec<_> = input.into_iter_strict(call.head)?.collect();
         let iter = v.into_iter().rev();
@@ -1,10 +1,13 @@
+use nu_engine::CallExt;
 use nu_protocol::ast::Call;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::{
-    Category, Example, IntoInterruptiblePipelineData, PipelineData, ShellError, Signature, Type,
+    Category, Example, IntoInterruptiblePipelineData, PipelineData, ShellError, Signature,
+    SyntaxShape, Type,
 };
 use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
 
 #[derive(Clone)]
 pub struct Shuffle;
@@ -20,6 +23,12 @@ impl Command for Shuffle {
                 Type::List(Box::new(Type::Any)),
                 Type::List(Box::new(Type::Any)),
             )])
+            .named(
+                "seed",
+                SyntaxShape::Int,
+                "seed the random generator for a reproducible order",
+                Some('s'),
+            )
             .category(Category::Filters)
     }
 
@@ -30,13 +39,17 @@ impl Command for Shuffle {
     fn run(
         &self,
         engine_state: &EngineState,
-        _stack: &mut Stack,
+        stack: &mut Stack,
         call: &Call,
         input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
+        let seed: Option<i64> = call.get_flag(engine_state, stack, "seed")?;
         let metadata = input.metadata();
         let mut v: Vec<_> = input.into_iter_strict(call.head)?.collect();
-        v.shuffle(&mut thread_rng());
+        match seed {
+            Some(seed) => v.shuffle(&mut StdRng::seed_from_u64(seed as u64)),
+            None => v.shuffle(&mut thread_rng()),
+        }
         let iter = v.into_iter();
         Ok(iter
             .into_pipeline_data(engine_state.ctrlc.clone())
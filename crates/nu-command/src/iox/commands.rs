@@ -0,0 +1,98 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Value,
+};
+
+/// Every iox-category decl known to the engine as `(name, usage)`, sorted
+/// by name. Backs `iox-commands` and is usable from tooling directly.
+pub fn iox_decls(engine_state: &EngineState) -> Vec<(String, String)> {
+    let mut decls: Vec<(String, String)> = (0..engine_state.num_decls())
+        .map(|id| engine_state.get_decl(id))
+        .filter(|decl| {
+            matches!(&decl.signature().category, Category::Custom(cat) if cat == "iox")
+        })
+        .map(|decl| (decl.name().to_string(), decl.usage().to_string()))
+        .collect();
+    decls.sort();
+    decls
+}
+
+#[derive(Clone)]
+pub struct IoxCommands;
+
+impl Command for IoxCommands {
+    fn name(&self) -> &str {
+        "iox-commands"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("iox-commands").category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "List the iox commands with their usage."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["influx", "introspection"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let vals = iox_decls(engine_state)
+            .into_iter()
+            .map(|(name, usage)| Value::Record {
+                cols: vec!["name".into(), "usage".into()],
+                vals: vec![Value::string(name, span), Value::string(usage, span)],
+                span,
+            })
+            .collect();
+        Ok(Value::List { vals, span }.into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![Example {
+            description: "Discover the iox subsystem",
+            example: "iox-commands",
+            result: None,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(IoxCommands {})
+    }
+
+    #[test]
+    fn known_iox_commands_are_listed_with_usage() {
+        let engine_state = crate::create_default_context();
+        let decls = iox_decls(&engine_state);
+        let names: Vec<&str> = decls.iter().map(|(name, _)| name.as_str()).collect();
+
+        for expected in ["iox-commands", "iox-config", "iox-session set", "ioxpredicate", "ioxtrace"] {
+            assert!(names.contains(&expected), "missing {expected} in {names:?}");
+        }
+        let (_, usage) = decls
+            .iter()
+            .find(|(name, _)| name == "ioxpredicate")
+            .unwrap();
+        assert_eq!(
+            usage,
+            "Parse and validate an IOx predicate expression without executing it."
+        );
+    }
+}
@@ -0,0 +1,415 @@
+// Shared IOx connection configuration files. Teams check one file into a
+// repo and everyone gets the same endpoint/token/database defaults:
+//
+//   endpoint = "http://localhost:8082"
+//   token = "..."
+//   dbname = "company_sensors"
+//   timeout_ms = 30000
+//
+//   [profiles.prod]
+//   endpoint = "https://iox.example.com"
+//
+// `iox-config --load` reads it into the environment, which sits below
+// explicit command flags in precedence.
+
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, PipelineData, ShellError, Signature, Spanned, SyntaxShape, Value,
+};
+
+use super::io_to_shell;
+
+/// The file format of a connection config, decided by file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Pick the format from a file path's extension.
+    pub fn from_path(path: &str) -> Result<Self, String> {
+        match path.rsplit_once('.').map(|(_, ext)| ext) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Ok(ConfigFormat::Toml),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(ConfigFormat::Json),
+            _ => Err(format!(
+                "cannot tell the config format of '{path}'; use a .toml or .json extension"
+            )),
+        }
+    }
+}
+
+/// Connection settings resolved from a config file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionConfig {
+    pub endpoint: Option<String>,
+    pub token: Option<String>,
+    pub dbname: Option<String>,
+    pub timeout_ms: Option<i64>,
+}
+
+impl ConnectionConfig {
+    /// The fields a usable config must provide, by name, that this one
+    /// doesn't. Only the endpoint is truly required; everything else has a
+    /// flag or per-command default.
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        let mut missing = vec![];
+        if self.endpoint.is_none() {
+            missing.push("endpoint");
+        }
+        missing
+    }
+
+    fn merge_from(&mut self, other: ConnectionConfig) {
+        if other.endpoint.is_some() {
+            self.endpoint = other.endpoint;
+        }
+        if other.token.is_some() {
+            self.token = other.token;
+        }
+        if other.dbname.is_some() {
+            self.dbname = other.dbname;
+        }
+        if other.timeout_ms.is_some() {
+            self.timeout_ms = other.timeout_ms;
+        }
+    }
+}
+
+/// Parse a config file's text. A `profile` picks a `[profiles.<name>]`
+/// section whose fields override the top-level ones.
+pub fn parse_connection_config(
+    text: &str,
+    format: ConfigFormat,
+    profile: Option<&str>,
+) -> Result<ConnectionConfig, String> {
+    let value = match format {
+        ConfigFormat::Toml => toml_to_json(
+            toml::from_str::<toml::Value>(text).map_err(|err| err.to_string())?,
+        ),
+        ConfigFormat::Json => nu_json::from_str(text).map_err(|err| err.to_string())?,
+    };
+
+    let mut config = config_from_object(&value)?;
+    if let Some(profile) = profile {
+        let section = value
+            .find("profiles")
+            .and_then(|profiles| profiles.find(profile))
+            .ok_or_else(|| format!("no profile named '{profile}' in the config"))?;
+        config.merge_from(config_from_object(section)?);
+    }
+    Ok(config)
+}
+
+fn config_from_object(value: &nu_json::Value) -> Result<ConnectionConfig, String> {
+    if !matches!(value, nu_json::Value::Object(_)) {
+        return Err("expected the config to be a table/object".into());
+    }
+    Ok(ConnectionConfig {
+        endpoint: string_field(value, "endpoint")?,
+        token: string_field(value, "token")?,
+        dbname: string_field(value, "dbname")?,
+        timeout_ms: int_field(value, "timeout_ms")?,
+    })
+}
+
+fn string_field(value: &nu_json::Value, key: &str) -> Result<Option<String>, String> {
+    match value.find(key) {
+        None => Ok(None),
+        Some(nu_json::Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(format!("'{key}' must be a string")),
+    }
+}
+
+fn int_field(value: &nu_json::Value, key: &str) -> Result<Option<i64>, String> {
+    match value.find(key) {
+        None => Ok(None),
+        Some(nu_json::Value::I64(i)) => Ok(Some(*i)),
+        Some(nu_json::Value::U64(u)) => i64::try_from(*u)
+            .map(Some)
+            .map_err(|_| format!("'{key}' is out of range")),
+        Some(_) => Err(format!("'{key}' must be an integer")),
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> nu_json::Value {
+    match value {
+        toml::Value::String(s) => nu_json::Value::String(s),
+        toml::Value::Integer(i) => nu_json::Value::I64(i),
+        toml::Value::Float(f) => nu_json::Value::F64(f),
+        toml::Value::Boolean(b) => nu_json::Value::Bool(b),
+        toml::Value::Datetime(d) => nu_json::Value::String(d.to_string()),
+        toml::Value::Array(entries) => {
+            nu_json::Value::Array(entries.into_iter().map(toml_to_json).collect())
+        }
+        toml::Value::Table(table) => nu_json::Value::Object(
+            table
+                .into_iter()
+                .map(|(key, val)| (key, toml_to_json(val)))
+                .collect(),
+        ),
+    }
+}
+
+/// Look for a `.iox` dotfile in `start_dir` or its parents and parse it as
+/// the TOML config format, so iox commands inside a project directory pick
+/// up project defaults. Sits below env vars in precedence (flag > env >
+/// dotfile > built-in default). A malformed dotfile yields a warning and
+/// no config rather than an error, so one bad file doesn't break every
+/// command run under that directory.
+pub fn load_dotfile(start_dir: &std::path::Path) -> (Option<ConnectionConfig>, Option<String>) {
+    for dir in start_dir.ancestors() {
+        let path = dir.join(".iox");
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        return match parse_connection_config(&text, ConfigFormat::Toml, None) {
+            Ok(config) => (Some(config), None),
+            Err(message) => (
+                None,
+                Some(format!("ignoring malformed {}: {message}", path.display())),
+            ),
+        };
+    }
+    (None, None)
+}
+
+#[derive(Clone)]
+pub struct IoxConfig;
+
+impl Command for IoxConfig {
+    fn name(&self) -> &str {
+        "iox-config"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("iox-config")
+            .required_named(
+                "load",
+                SyntaxShape::Filepath,
+                "the TOML or JSON config file to load",
+                Some('l'),
+            )
+            .named(
+                "profile",
+                SyntaxShape::String,
+                "profile whose settings override the top-level ones",
+                Some('p'),
+            )
+            .category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Load IOx connection settings from a config file into the environment."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Sets IOX_ADDR, IOX_TOKEN, IOX_DBNAME and IOX_TIMEOUT_MS. Explicit command flags still take precedence."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let path: Spanned<String> = call
+            .get_flag(engine_state, stack, "load")?
+            .expect("required named flag");
+        let profile: Option<Spanned<String>> =
+            call.get_flag(engine_state, stack, "profile")?;
+        let span = call.head;
+
+        let format = ConfigFormat::from_path(&path.item).map_err(|msg| {
+            ShellError::GenericError(msg, "unsupported extension".into(), Some(path.span), None, Vec::new())
+        })?;
+        let text = std::fs::read_to_string(&path.item)
+            .map_err(|err| io_to_shell(err, "reading the config file", path.span))?;
+
+        let config = parse_connection_config(
+            &text,
+            format,
+            profile.as_ref().map(|p| p.item.as_str()),
+        )
+        .map_err(|msg| {
+            ShellError::GenericError(
+                "invalid iox config".into(),
+                msg,
+                Some(path.span),
+                None,
+                Vec::new(),
+            )
+        })?;
+
+        let missing = config.missing_fields();
+        if !missing.is_empty() {
+            return Err(ShellError::GenericError(
+                "incomplete iox config".into(),
+                format!("missing required field(s): {}", missing.join(", ")),
+                Some(path.span),
+                None,
+                Vec::new(),
+            ));
+        }
+
+        if let Some(endpoint) = config.endpoint {
+            stack.add_env_var("IOX_ADDR".into(), Value::string(endpoint, span));
+        }
+        if let Some(token) = config.token {
+            stack.add_env_var("IOX_TOKEN".into(), Value::string(token, span));
+        }
+        if let Some(dbname) = config.dbname {
+            stack.add_env_var("IOX_DBNAME".into(), Value::string(dbname, span));
+        }
+        if let Some(timeout) = config.timeout_ms {
+            stack.add_env_var("IOX_TIMEOUT_MS".into(), Value::int(timeout, span));
+        }
+        Ok(PipelineData::empty())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Load a team config",
+                example: "iox-config --load iox.toml",
+                result: None,
+            },
+            Example {
+                description: "Load the production profile from a shared config",
+                example: "iox-config --load iox.toml --profile prod",
+                result: None,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(IoxConfig {})
+    }
+
+    const SAMPLE_TOML: &str = r#"
+endpoint = "http://localhost:8082"
+token = "local-token"
+dbname = "company_sensors"
+timeout_ms = 30000
+
+[profiles.prod]
+endpoint = "https://iox.example.com"
+token = "prod-token"
+"#;
+
+    #[test]
+    fn toml_config_resolves_all_fields() {
+        let config =
+            parse_connection_config(SAMPLE_TOML, ConfigFormat::Toml, None).unwrap();
+        assert_eq!(config.endpoint.as_deref(), Some("http://localhost:8082"));
+        assert_eq!(config.token.as_deref(), Some("local-token"));
+        assert_eq!(config.dbname.as_deref(), Some("company_sensors"));
+        assert_eq!(config.timeout_ms, Some(30000));
+        assert!(config.missing_fields().is_empty());
+    }
+
+    #[test]
+    fn profile_overrides_top_level_fields() {
+        let config =
+            parse_connection_config(SAMPLE_TOML, ConfigFormat::Toml, Some("prod")).unwrap();
+        assert_eq!(config.endpoint.as_deref(), Some("https://iox.example.com"));
+        assert_eq!(config.token.as_deref(), Some("prod-token"));
+        // fields the profile doesn't set fall through to the top level
+        assert_eq!(config.dbname.as_deref(), Some("company_sensors"));
+    }
+
+    #[test]
+    fn unknown_profile_is_an_error() {
+        let err =
+            parse_connection_config(SAMPLE_TOML, ConfigFormat::Toml, Some("staging"))
+                .unwrap_err();
+        assert!(err.contains("staging"));
+    }
+
+    #[test]
+    fn json_config_parses_too() {
+        let config = parse_connection_config(
+            r#"{"endpoint": "http://localhost:8082", "timeout_ms": 5000}"#,
+            ConfigFormat::Json,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.endpoint.as_deref(), Some("http://localhost:8082"));
+        assert_eq!(config.timeout_ms, Some(5000));
+    }
+
+    #[test]
+    fn missing_endpoint_is_reported() {
+        let config = parse_connection_config(
+            r#"token = "only-a-token""#,
+            ConfigFormat::Toml,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.missing_fields(), vec!["endpoint"]);
+    }
+
+    #[test]
+    fn format_is_decided_by_extension() {
+        assert_eq!(ConfigFormat::from_path("a.toml"), Ok(ConfigFormat::Toml));
+        assert_eq!(ConfigFormat::from_path("a.JSON"), Ok(ConfigFormat::Json));
+        assert!(ConfigFormat::from_path("a.yaml").is_err());
+        assert!(ConfigFormat::from_path("noext").is_err());
+    }
+
+    #[test]
+    fn dotfile_is_found_in_a_parent_directory() {
+        let root = std::env::temp_dir().join(format!("iox-dotfile-{}", std::process::id()));
+        let nested = root.join("project").join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            root.join("project").join(".iox"),
+            "endpoint = \"http://localhost:8082\"\ndbname = \"project_db\"\n",
+        )
+        .unwrap();
+
+        let (config, warning) = load_dotfile(&nested);
+        assert!(warning.is_none());
+        assert_eq!(config.unwrap().dbname.as_deref(), Some("project_db"));
+
+        let (config, _) = load_dotfile(std::path::Path::new("/nonexistent/elsewhere"));
+        assert!(config.is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn malformed_dotfile_warns_instead_of_failing() {
+        let root = std::env::temp_dir().join(format!("iox-dotfile-bad-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join(".iox"), "endpoint = [not toml").unwrap();
+
+        let (config, warning) = load_dotfile(&root);
+        assert!(config.is_none());
+        assert!(warning.unwrap().contains(".iox"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn wrong_field_type_is_an_error() {
+        let err = parse_connection_config(
+            r#"endpoint = 99"#,
+            ConfigFormat::Toml,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("endpoint"));
+    }
+}
@@ -0,0 +1,221 @@
+// A minimal expression tree for IOx predicates, shaped after the DataFusion
+// Expr the server evaluates. The rewriters in rewrite.rs operate on this.
+
+/// The types IOx predicates deal in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataType {
+    Boolean,
+    Int64,
+    Float64,
+    Utf8,
+}
+
+/// A typed literal, `None` meaning a typed NULL.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScalarValue {
+    Boolean(Option<bool>),
+    Int64(Option<i64>),
+    Float64(Option<f64>),
+    Utf8(Option<String>),
+}
+
+impl ScalarValue {
+    pub fn is_null(&self) -> bool {
+        matches!(
+            self,
+            ScalarValue::Boolean(None)
+                | ScalarValue::Int64(None)
+                | ScalarValue::Float64(None)
+                | ScalarValue::Utf8(None)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Modulo,
+    StringConcat,
+}
+
+impl Operator {
+    /// Comparison operators return a boolean and propagate NULL operands to
+    /// a NULL result; several rewrites rely on exactly that behavior.
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Column(String),
+    Literal(ScalarValue),
+    BinaryExpr {
+        left: Box<Expr>,
+        op: Operator,
+        right: Box<Expr>,
+    },
+    Not(Box<Expr>),
+    IsNull(Box<Expr>),
+    IsNotNull(Box<Expr>),
+    Case {
+        /// `CASE <expr> WHEN ...` discriminant; `None` for the searched form.
+        expr: Option<Box<Expr>>,
+        when_then: Vec<(Expr, Expr)>,
+        else_expr: Option<Box<Expr>>,
+    },
+    Cast {
+        expr: Box<Expr>,
+        data_type: DataType,
+    },
+}
+
+// `not` and `eq` intentionally mirror the DataFusion Expr builder names
+// rather than the std operator traits.
+#[allow(clippy::should_implement_trait)]
+impl Expr {
+    pub fn not(self) -> Expr {
+        Expr::Not(Box::new(self))
+    }
+
+    pub fn is_null(self) -> Expr {
+        Expr::IsNull(Box::new(self))
+    }
+
+    pub fn is_not_null(self) -> Expr {
+        Expr::IsNotNull(Box::new(self))
+    }
+
+    pub fn cast(self, data_type: DataType) -> Expr {
+        Expr::Cast {
+            expr: Box::new(self),
+            data_type,
+        }
+    }
+
+    pub fn eq(self, other: Expr) -> Expr {
+        binary_expr(self, Operator::Eq, other)
+    }
+
+    pub fn and(self, other: Expr) -> Expr {
+        binary_expr(self, Operator::And, other)
+    }
+
+    pub fn or(self, other: Expr) -> Expr {
+        binary_expr(self, Operator::Or, other)
+    }
+}
+
+pub fn col(name: impl Into<String>) -> Expr {
+    Expr::Column(name.into())
+}
+
+pub fn lit_str(value: impl Into<String>) -> Expr {
+    Expr::Literal(ScalarValue::Utf8(Some(value.into())))
+}
+
+pub fn lit_int(value: i64) -> Expr {
+    Expr::Literal(ScalarValue::Int64(Some(value)))
+}
+
+pub fn lit_float(value: f64) -> Expr {
+    Expr::Literal(ScalarValue::Float64(Some(value)))
+}
+
+pub fn lit_bool(value: bool) -> Expr {
+    Expr::Literal(ScalarValue::Boolean(Some(value)))
+}
+
+pub fn binary_expr(left: Expr, op: Operator, right: Expr) -> Expr {
+    Expr::BinaryExpr {
+        left: Box::new(left),
+        op,
+        right: Box::new(right),
+    }
+}
+
+/// Rewrites an expression tree node by node.
+pub trait ExprRewriter {
+    fn mutate(&mut self, expr: Expr) -> Expr;
+}
+
+/// Traversal direction for [`rewrite_expr_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RewriteOrder {
+    /// Parent before children; what [`rewrite_expr`] uses.
+    TopDown,
+    /// Children before parent, so inner simplifications feed outer ones.
+    BottomUp,
+}
+
+/// Apply `rewriter` over the whole tree, parent before children.
+pub fn rewrite_expr(expr: Expr, rewriter: &mut impl ExprRewriter) -> Expr {
+    rewrite_expr_with(expr, rewriter, RewriteOrder::TopDown)
+}
+
+/// Apply `rewriter` over the whole tree in the given traversal order.
+pub fn rewrite_expr_with(
+    expr: Expr,
+    rewriter: &mut impl ExprRewriter,
+    order: RewriteOrder,
+) -> Expr {
+    match order {
+        RewriteOrder::TopDown => {
+            let expr = rewriter.mutate(expr);
+            map_children(expr, |child| rewrite_expr_with(child, rewriter, order))
+        }
+        RewriteOrder::BottomUp => {
+            let expr = map_children(expr, |child| rewrite_expr_with(child, rewriter, order));
+            rewriter.mutate(expr)
+        }
+    }
+}
+
+pub(super) fn map_children(expr: Expr, mut f: impl FnMut(Expr) -> Expr) -> Expr {
+    match expr {
+        Expr::Column(_) | Expr::Literal(_) => expr,
+        Expr::BinaryExpr { left, op, right } => Expr::BinaryExpr {
+            left: Box::new(f(*left)),
+            op,
+            right: Box::new(f(*right)),
+        },
+        Expr::Not(inner) => Expr::Not(Box::new(f(*inner))),
+        Expr::IsNull(inner) => Expr::IsNull(Box::new(f(*inner))),
+        Expr::IsNotNull(inner) => Expr::IsNotNull(Box::new(f(*inner))),
+        Expr::Case {
+            expr,
+            when_then,
+            else_expr,
+        } => Expr::Case {
+            expr: expr.map(|e| Box::new(f(*e))),
+            when_then: when_then
+                .into_iter()
+                .map(|(when, then)| (f(when), f(then)))
+                .collect(),
+            else_expr: else_expr.map(|e| Box::new(f(*e))),
+        },
+        Expr::Cast { expr, data_type } => Expr::Cast {
+            expr: Box::new(f(*expr)),
+            data_type,
+        },
+    }
+}
@@ -0,0 +1,135 @@
+// Collapsing nested records into dotted column names so deeply nested JSON
+// query results can be used with flat `select`/`where` pipelines:
+//   {addr: {city: x}}  ->  {addr.city: x}
+// Arrays are either indexed into the column name (`tags.0`) or left as
+// list values, controlled by the caller.
+
+use nu_protocol::Value;
+
+/// Flatten nested records in `value` into dotted column names. With
+/// `flatten_arrays`, list entries are flattened too, using the index as a
+/// path segment; otherwise lists are kept as list values. Non-record input
+/// is returned unchanged.
+pub fn flatten_columns(value: Value, flatten_arrays: bool) -> Value {
+    match value {
+        Value::Record { cols, vals, span } => {
+            let mut flat_cols = vec![];
+            let mut flat_vals = vec![];
+            for (col, val) in cols.into_iter().zip(vals) {
+                flatten_into(&col, val, flatten_arrays, &mut flat_cols, &mut flat_vals);
+            }
+            Value::Record {
+                cols: flat_cols,
+                vals: flat_vals,
+                span,
+            }
+        }
+        other => other,
+    }
+}
+
+fn flatten_into(
+    path: &str,
+    value: Value,
+    flatten_arrays: bool,
+    cols: &mut Vec<String>,
+    vals: &mut Vec<Value>,
+) {
+    match value {
+        Value::Record { cols: inner_cols, vals: inner_vals, .. } => {
+            for (col, val) in inner_cols.into_iter().zip(inner_vals) {
+                flatten_into(&format!("{path}.{col}"), val, flatten_arrays, cols, vals);
+            }
+        }
+        Value::List {
+            vals: entries,
+            span: _,
+        } if flatten_arrays => {
+            for (idx, entry) in entries.into_iter().enumerate() {
+                flatten_into(&format!("{path}.{idx}"), entry, flatten_arrays, cols, vals);
+            }
+        }
+        leaf => {
+            cols.push(path.to_string());
+            vals.push(leaf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_level_nesting_flattens_to_dotted_columns() {
+        let nested = Value::test_record(
+            vec!["name", "addr"],
+            vec![
+                Value::test_string("ada"),
+                Value::test_record(
+                    vec!["city", "geo"],
+                    vec![
+                        Value::test_string("london"),
+                        Value::test_record(
+                            vec!["lat"],
+                            vec![Value::test_float(51.5)],
+                        ),
+                    ],
+                ),
+            ],
+        );
+
+        let flat = flatten_columns(nested, false);
+        assert_eq!(
+            flat,
+            Value::test_record(
+                vec!["name", "addr.city", "addr.geo.lat"],
+                vec![
+                    Value::test_string("ada"),
+                    Value::test_string("london"),
+                    Value::test_float(51.5),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn arrays_stay_as_lists_by_default() {
+        let record = Value::test_record(
+            vec!["tags"],
+            vec![Value::List {
+                vals: vec![Value::test_string("a"), Value::test_string("b")],
+                span: nu_protocol::Span::test_data(),
+            }],
+        );
+
+        let flat = flatten_columns(record.clone(), false);
+        assert_eq!(flat, record);
+    }
+
+    #[test]
+    fn arrays_flatten_with_index_suffixes_when_asked() {
+        let record = Value::test_record(
+            vec!["tags"],
+            vec![Value::List {
+                vals: vec![Value::test_string("a"), Value::test_string("b")],
+                span: nu_protocol::Span::test_data(),
+            }],
+        );
+
+        let flat = flatten_columns(record, true);
+        assert_eq!(
+            flat,
+            Value::test_record(
+                vec!["tags.0", "tags.1"],
+                vec![Value::test_string("a"), Value::test_string("b")],
+            )
+        );
+    }
+
+    #[test]
+    fn non_record_input_is_unchanged() {
+        let value = Value::test_int(3);
+        assert_eq!(flatten_columns(value.clone(), true), value);
+    }
+}
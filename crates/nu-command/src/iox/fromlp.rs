@@ -0,0 +1,157 @@
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, Type, Value,
+};
+
+use super::lp::{parse_lp, FieldValue, ParsedLine};
+
+#[derive(Clone)]
+pub struct IoxFromLp;
+
+impl Command for IoxFromLp {
+    fn name(&self) -> &str {
+        "from lp"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("from lp")
+            .input_output_types(vec![(Type::String, Type::Table(vec![]))])
+            .category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Parse InfluxDB line protocol into a table."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Each line becomes a record with a measurement column, one column per tag and field, and a time column holding the nanosecond timestamp when the line carries one."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["iox", "line protocol", "influx"]
+    }
+
+    fn run(
+        &self,
+        _engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let head = call.head;
+        let (text, span, _metadata) = input.collect_string_strict(head)?;
+
+        let lines = parse_lp(&text).map_err(|err| {
+            ShellError::GenericError(
+                "failed to parse line protocol".into(),
+                format!("line {}: {}", err.line, err.message),
+                Some(span),
+                None,
+                Vec::new(),
+            )
+        })?;
+
+        let vals = lines
+            .iter()
+            .map(|line| line_to_record(line, head))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Value::List { vals, span: head }.into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![Example {
+            description: "Parse a line of line protocol",
+            example: r#"'cpu,region=west usage=0.5,cores=4i 100' | from lp"#,
+            result: Some(Value::List {
+                vals: vec![Value::Record {
+                    cols: vec![
+                        "measurement".into(),
+                        "region".into(),
+                        "usage".into(),
+                        "cores".into(),
+                        "time".into(),
+                    ],
+                    vals: vec![
+                        Value::test_string("cpu"),
+                        Value::test_string("west"),
+                        Value::test_float(0.5),
+                        Value::test_int(4),
+                        Value::test_int(100),
+                    ],
+                    span: Span::test_data(),
+                }],
+                span: Span::test_data(),
+            }),
+        }]
+    }
+}
+
+fn line_to_record(line: &ParsedLine, span: Span) -> Result<Value, ShellError> {
+    let mut cols = vec!["measurement".to_string()];
+    let mut vals = vec![Value::string(&line.measurement, span)];
+
+    for (key, value) in &line.tags {
+        cols.push(key.clone());
+        vals.push(Value::string(value, span));
+    }
+    for (key, value) in &line.fields {
+        cols.push(key.clone());
+        vals.push(field_to_value(key, value, span)?);
+    }
+    if let Some(ts) = line.timestamp {
+        cols.push("time".into());
+        vals.push(Value::int(ts, span));
+    }
+
+    Ok(Value::Record { cols, vals, span })
+}
+
+fn field_to_value(key: &str, value: &FieldValue, span: Span) -> Result<Value, ShellError> {
+    Ok(match value {
+        FieldValue::Float(val) => Value::float(*val, span),
+        FieldValue::Int(val) => Value::int(*val, span),
+        FieldValue::UInt(val) => {
+            let val = i64::try_from(*val).map_err(|_| {
+                ShellError::GenericError(
+                    format!("field '{key}' does not fit in an int"),
+                    format!("{val} exceeds the largest supported integer"),
+                    Some(span),
+                    None,
+                    Vec::new(),
+                )
+            })?;
+            Value::int(val, span)
+        }
+        FieldValue::Bool(val) => Value::boolean(*val, span),
+        FieldValue::String(val) => Value::string(val, span),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(IoxFromLp {})
+    }
+
+    #[test]
+    fn lines_without_timestamp_omit_the_time_column() {
+        let lines = parse_lp("cpu usage=1i").unwrap();
+        let record = line_to_record(&lines[0], Span::test_data()).unwrap();
+        let Value::Record { cols, .. } = record else {
+            panic!("expected a record")
+        };
+        assert_eq!(cols, ["measurement", "usage"]);
+    }
+
+    #[test]
+    fn oversized_unsigned_fields_error() {
+        let lines = parse_lp("cpu free=18446744073709551615u").unwrap();
+        assert!(line_to_record(&lines[0], Span::test_data()).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+// Post-parse type inference for delimited data. The delimited parser
+// recognizes ints and floats but leaves `true`/`false` cells as strings;
+// this pass upgrades whole columns to booleans when every cell agrees.
+
+use nu_protocol::Value;
+
+/// The string tokens recognized as booleans, lowercase. The defaults match
+/// what line protocol accepts; extend the sets for data that uses e.g.
+/// `yes`/`no`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoolTokens {
+    pub true_tokens: Vec<String>,
+    pub false_tokens: Vec<String>,
+}
+
+impl Default for BoolTokens {
+    fn default() -> Self {
+        BoolTokens {
+            true_tokens: vec!["true".into(), "t".into()],
+            false_tokens: vec!["false".into(), "f".into()],
+        }
+    }
+}
+
+impl BoolTokens {
+    /// Interpret one cell, case-insensitively.
+    fn parse(&self, cell: &str) -> Option<bool> {
+        let lower = cell.trim().to_ascii_lowercase();
+        if self.true_tokens.contains(&lower) {
+            return Some(true);
+        }
+        if self.false_tokens.contains(&lower) {
+            return Some(false);
+        }
+        None
+    }
+}
+
+/// Convert string columns to booleans where every row's cell is a
+/// recognized token. A column mixing booleans with anything else (other
+/// strings, numbers the parser already typed) is left untouched, so no
+/// value is reinterpreted in a column that isn't uniformly boolean.
+pub fn infer_bool_columns(rows: &mut [Value], tokens: &BoolTokens) {
+    let Some(Value::Record { cols, .. }) = rows.first() else {
+        return;
+    };
+
+    let boolean_columns: Vec<String> = cols
+        .iter()
+        .filter(|col| {
+            rows.iter().all(|row| {
+                matches!(
+                    column_value(row, col),
+                    Some(Value::String { val, .. }) if tokens.parse(val).is_some()
+                )
+            })
+        })
+        .cloned()
+        .collect();
+
+    for row in rows {
+        let Value::Record { cols, vals, .. } = row else {
+            continue;
+        };
+        for (col, val) in cols.iter().zip(vals) {
+            if boolean_columns.contains(col) {
+                if let Value::String { val: cell, span } = val {
+                    if let Some(parsed) = tokens.parse(cell) {
+                        *val = Value::Bool {
+                            val: parsed,
+                            span: *span,
+                        };
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn column_value<'a>(row: &'a Value, column: &str) -> Option<&'a Value> {
+    match row {
+        Value::Record { cols, vals, .. } => cols
+            .iter()
+            .position(|c| c == column)
+            .map(|idx| &vals[idx]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(ok: &str, host: &str) -> Value {
+        Value::test_record(
+            vec!["ok", "host"],
+            vec![Value::test_string(ok), Value::test_string(host)],
+        )
+    }
+
+    #[test]
+    fn pure_boolean_column_is_converted() {
+        let mut rows = vec![row("true", "a"), row("F", "b"), row("t", "false")];
+        infer_bool_columns(&mut rows, &BoolTokens::default());
+
+        assert_eq!(
+            rows[0],
+            Value::test_record(
+                vec!["ok", "host"],
+                vec![Value::test_bool(true), Value::test_string("a")],
+            )
+        );
+        assert_eq!(
+            rows[1],
+            Value::test_record(
+                vec!["ok", "host"],
+                vec![Value::test_bool(false), Value::test_string("b")],
+            )
+        );
+        // "false" in the host column stays a string: the column is mixed
+        assert_eq!(
+            rows[2],
+            Value::test_record(
+                vec!["ok", "host"],
+                vec![Value::test_bool(true), Value::test_string("false")],
+            )
+        );
+    }
+
+    #[test]
+    fn mixed_column_stays_string() {
+        let mut rows = vec![row("true", "a"), row("maybe", "b")];
+        let before = rows.clone();
+        infer_bool_columns(&mut rows, &BoolTokens::default());
+        assert_eq!(rows, before);
+    }
+
+    #[test]
+    fn token_set_is_configurable() {
+        let tokens = BoolTokens {
+            true_tokens: vec!["yes".into()],
+            false_tokens: vec!["no".into()],
+        };
+        let mut rows = vec![row("yes", "a"), row("no", "b")];
+        infer_bool_columns(&mut rows, &tokens);
+        assert_eq!(
+            rows[1],
+            Value::test_record(
+                vec!["ok", "host"],
+                vec![Value::test_bool(false), Value::test_string("b")],
+            )
+        );
+
+        // the default tokens no longer apply
+        let mut rows = vec![row("true", "a")];
+        infer_bool_columns(&mut rows, &tokens);
+        assert_eq!(rows[0], row("true", "a"));
+    }
+}
@@ -0,0 +1,118 @@
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Spanned,
+    SyntaxShape, Value,
+};
+
+use super::io_to_shell;
+use super::trace::{global_trace_collector, spans_to_json, Span as TraceSpan};
+
+#[derive(Clone)]
+pub struct Ioxtrace;
+
+impl Command for Ioxtrace {
+    fn name(&self) -> &str {
+        "ioxtrace"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("ioxtrace")
+            .named(
+                "export",
+                SyntaxShape::Filepath,
+                "write the buffered spans to a JSON file",
+                Some('e'),
+            )
+            .switch("clear", "empty the span buffer afterwards", Some('c'))
+            .category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Show, export or clear the spans buffered by the iox trace collector."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["influx", "span"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let export: Option<Spanned<String>> = call.get_flag(engine_state, stack, "export")?;
+        let clear = call.has_flag("clear");
+        let span = call.head;
+
+        let collector = global_trace_collector();
+        let spans = collector.spans();
+
+        let output = if let Some(path) = export {
+            std::fs::write(&path.item, spans_to_json(&spans))
+                .map_err(|err| io_to_shell(err, "writing the trace export", path.span))?;
+            Value::string(
+                format!("exported {} span(s) to {}", spans.len(), path.item),
+                span,
+            )
+        } else {
+            Value::List {
+                vals: spans.iter().map(|s| span_row(s, span)).collect(),
+                span,
+            }
+        };
+
+        if clear {
+            collector.clear();
+        }
+        Ok(output.into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "List the buffered spans",
+                example: "ioxtrace",
+                result: None,
+            },
+            Example {
+                description: "Attach a trace to a bug report, then start fresh",
+                example: "ioxtrace --export traces.json --clear",
+                result: None,
+            },
+        ]
+    }
+}
+
+fn span_row(span: &TraceSpan, head: nu_protocol::Span) -> Value {
+    Value::Record {
+        cols: vec![
+            "name".into(),
+            "trace_id".into(),
+            "span_id".into(),
+            "status".into(),
+            "events".into(),
+        ],
+        vals: vec![
+            Value::string(span.name.to_string(), head),
+            Value::string(format!("{:x}", span.ctx.trace_id.0), head),
+            Value::string(format!("{:x}", span.ctx.span_id.0), head),
+            Value::string(format!("{:?}", span.status), head),
+            Value::int(span.events.len() as i64, head),
+        ],
+        span: head,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(super::Ioxtrace {})
+    }
+}
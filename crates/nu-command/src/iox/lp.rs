@@ -0,0 +1,457 @@
+// Parsing and serializing InfluxDB line protocol:
+//   measurement[,tag=value...] field=value[,field=value...] [timestamp]
+// Timestamps are nanoseconds since the Unix epoch and may be negative for
+// pre-1970 data.
+
+use chrono::{DateTime, TimeZone, Utc};
+use nu_protocol::Value;
+
+/// Convert an IOx nanosecond timestamp to a date, including negative
+/// (pre-epoch) values.
+pub fn timestamp_to_datetime(ns: i64) -> DateTime<Utc> {
+    Utc.timestamp_nanos(ns)
+}
+
+/// The inverse of [`timestamp_to_datetime`].
+pub fn datetime_to_timestamp(date: &DateTime<Utc>) -> i64 {
+    date.timestamp_nanos()
+}
+
+/// The value of a single line-protocol field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Float(f64),
+    Int(i64),
+    UInt(u64),
+    Bool(bool),
+    String(String),
+}
+
+/// One parsed line of line protocol.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedLine {
+    pub measurement: String,
+    pub tags: Vec<(String, String)>,
+    pub fields: Vec<(String, FieldValue)>,
+    pub timestamp: Option<i64>,
+}
+
+/// A parse failure, pointing at the (1-based) offending line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LpParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Parse a whole line-protocol document. Blank lines and `#` comments are
+/// skipped; any malformed line fails the parse.
+pub fn parse_lp(text: &str) -> Result<Vec<ParsedLine>, LpParseError> {
+    let mut lines = vec![];
+    for (idx, raw) in text.lines().enumerate() {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        lines.push(parse_line(trimmed).map_err(|message| LpParseError {
+            line: idx + 1,
+            message,
+        })?);
+    }
+    Ok(lines)
+}
+
+fn parse_line(line: &str) -> Result<ParsedLine, String> {
+    // split into measurement+tags / fields / timestamp on unescaped,
+    // unquoted spaces
+    let sections = split_unescaped(line, ' ')?;
+    if sections.is_empty() || sections.len() > 3 {
+        return Err(format!("expected 2 or 3 sections, found {}", sections.len()));
+    }
+    if sections.len() < 2 {
+        return Err("missing fields section".into());
+    }
+
+    let mut measurement_and_tags = split_unescaped(&sections[0], ',')?.into_iter();
+    let measurement = unescape(
+        &measurement_and_tags
+            .next()
+            .filter(|m| !m.is_empty())
+            .ok_or("missing measurement")?,
+    );
+
+    let mut tags = vec![];
+    for tag in measurement_and_tags {
+        let (key, value) = split_key_value(&tag)?;
+        tags.push((unescape(&key), unescape(&value)));
+    }
+
+    let mut fields = vec![];
+    for field in split_unescaped(&sections[1], ',')? {
+        let (key, value) = split_key_value(&field)?;
+        fields.push((unescape(&key), parse_field_value(&value)?));
+    }
+    if fields.is_empty() {
+        return Err("at least one field is required".into());
+    }
+
+    let timestamp = match sections.get(2) {
+        Some(ts) => Some(
+            ts.parse::<i64>()
+                .map_err(|_| format!("invalid timestamp '{ts}'"))?,
+        ),
+        None => None,
+    };
+
+    Ok(ParsedLine {
+        measurement,
+        tags,
+        fields,
+        timestamp,
+    })
+}
+
+fn split_key_value(part: &str) -> Result<(String, String), String> {
+    let pieces = split_unescaped(part, '=')?;
+    match <[String; 2]>::try_from(pieces) {
+        Ok([key, value]) if !key.is_empty() => Ok((key, value)),
+        _ => Err(format!("expected key=value, found '{part}'")),
+    }
+}
+
+fn parse_field_value(raw: &str) -> Result<FieldValue, String> {
+    if let Some(quoted) = raw.strip_prefix('"') {
+        let inner = quoted
+            .strip_suffix('"')
+            .ok_or_else(|| format!("unterminated string value {raw}"))?;
+        return Ok(FieldValue::String(
+            inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        ));
+    }
+    if let Some(int) = raw.strip_suffix('i') {
+        return int
+            .parse()
+            .map(FieldValue::Int)
+            .map_err(|_| format!("invalid integer field '{raw}'"));
+    }
+    if let Some(uint) = raw.strip_suffix('u') {
+        return uint
+            .parse()
+            .map(FieldValue::UInt)
+            .map_err(|_| format!("invalid unsigned field '{raw}'"));
+    }
+    match raw {
+        "t" | "T" | "true" | "True" | "TRUE" => return Ok(FieldValue::Bool(true)),
+        "f" | "F" | "false" | "False" | "FALSE" => return Ok(FieldValue::Bool(false)),
+        _ => {}
+    }
+    raw.parse()
+        .map(FieldValue::Float)
+        .map_err(|_| format!("invalid field value '{raw}'"))
+}
+
+/// Split on `separator`, honoring backslash escapes and double-quoted
+/// strings (which only occur in field values).
+fn split_unescaped(text: &str, separator: char) -> Result<Vec<String>, String> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                match chars.next() {
+                    Some(escaped) => current.push(escaped),
+                    None => return Err("dangling escape at end of line".into()),
+                }
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c == separator && !in_quotes => {
+                parts.push(std::mem::take(&mut current));
+            }
+            c => current.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("unterminated string".into());
+    }
+    parts.push(current);
+    Ok(parts)
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(escaped) = chars.next() {
+                out.push(escaped);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, ',' | '=' | ' ' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Serialize one line back to line protocol; the inverse of [`parse_line`].
+pub fn line_to_lp(line: &ParsedLine) -> String {
+    let mut out = escape(&line.measurement);
+    for (key, value) in &line.tags {
+        out.push(',');
+        out.push_str(&escape(key));
+        out.push('=');
+        out.push_str(&escape(value));
+    }
+    out.push(' ');
+    let fields: Vec<String> = line
+        .fields
+        .iter()
+        .map(|(key, value)| format!("{}={}", escape(key), field_value_to_lp(value)))
+        .collect();
+    out.push_str(&fields.join(","));
+    if let Some(ts) = line.timestamp {
+        out.push(' ');
+        out.push_str(&ts.to_string());
+    }
+    out
+}
+
+fn field_value_to_lp(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Float(f) => f.to_string(),
+        FieldValue::Int(i) => format!("{i}i"),
+        FieldValue::UInt(u) => format!("{u}u"),
+        FieldValue::Bool(b) => b.to_string(),
+        FieldValue::String(s) => {
+            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+    }
+}
+
+/// Convert tabular rows (e.g. from `from csv`) to line protocol, one line
+/// per row. `tags`/`fields` name the columns to use for each role and
+/// `time` optionally names a nanosecond timestamp column. Field values
+/// keep their nushell type; CSV string cells are inferred as int, float or
+/// bool where they parse as one, since `from csv` yields strings.
+pub fn rows_to_lp(
+    measurement: &str,
+    tags: &[String],
+    fields: &[String],
+    time: Option<&str>,
+    rows: &[Value],
+) -> Result<Vec<String>, String> {
+    rows.iter()
+        .enumerate()
+        .map(|(idx, row)| {
+            row_to_lp(measurement, tags, fields, time, row)
+                .map_err(|message| format!("row {}: {message}", idx + 1))
+        })
+        .map(|line| line.map(|l| line_to_lp(&l)))
+        .collect()
+}
+
+fn row_to_lp(
+    measurement: &str,
+    tags: &[String],
+    fields: &[String],
+    time: Option<&str>,
+    row: &Value,
+) -> Result<ParsedLine, String> {
+    let mut line = ParsedLine {
+        measurement: measurement.to_string(),
+        tags: vec![],
+        fields: vec![],
+        timestamp: None,
+    };
+
+    for tag in tags {
+        let value = column_value(row, tag)?;
+        line.tags.push((
+            tag.clone(),
+            value.as_string().map_err(|_| format!("tag '{tag}' is not a string"))?,
+        ));
+    }
+
+    for field in fields {
+        line.fields
+            .push((field.clone(), infer_field_value(field, &column_value(row, field)?)?));
+    }
+    if line.fields.is_empty() {
+        return Err("at least one field column is required".into());
+    }
+
+    if let Some(time) = time {
+        line.timestamp = Some(match column_value(row, time)? {
+            Value::Int { val, .. } => val,
+            Value::String { val, .. } => val
+                .parse()
+                .map_err(|_| format!("time column '{time}' is not a nanosecond timestamp"))?,
+            Value::Date { val, .. } => val.timestamp_nanos(),
+            _ => return Err(format!("time column '{time}' is not a timestamp")),
+        });
+    }
+
+    Ok(line)
+}
+
+fn column_value(row: &Value, column: &str) -> Result<Value, String> {
+    match row {
+        Value::Record { cols, vals, .. } => cols
+            .iter()
+            .position(|c| c == column)
+            .map(|idx| vals[idx].clone())
+            .ok_or_else(|| format!("missing column '{column}'")),
+        _ => Err("expected a record per row".into()),
+    }
+}
+
+fn infer_field_value(field: &str, value: &Value) -> Result<FieldValue, String> {
+    match value {
+        Value::Int { val, .. } => Ok(FieldValue::Int(*val)),
+        Value::Float { val, .. } => Ok(FieldValue::Float(*val)),
+        Value::Bool { val, .. } => Ok(FieldValue::Bool(*val)),
+        Value::String { val, .. } => Ok(if let Ok(i) = val.parse::<i64>() {
+            FieldValue::Int(i)
+        } else if let Ok(f) = val.parse::<f64>() {
+            FieldValue::Float(f)
+        } else if let Ok(b) = val.parse::<bool>() {
+            FieldValue::Bool(b)
+        } else {
+            FieldValue::String(val.clone())
+        }),
+        _ => Err(format!("field '{field}' has an unsupported type")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_full_line() {
+        let lines = parse_lp("cpu,region=west,host=a usage=0.5,cores=4i,ok=true 1234").unwrap();
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.measurement, "cpu");
+        assert_eq!(
+            line.tags,
+            vec![
+                ("region".to_string(), "west".to_string()),
+                ("host".to_string(), "a".to_string())
+            ]
+        );
+        assert_eq!(
+            line.fields,
+            vec![
+                ("usage".to_string(), FieldValue::Float(0.5)),
+                ("cores".to_string(), FieldValue::Int(4)),
+                ("ok".to_string(), FieldValue::Bool(true)),
+            ]
+        );
+        assert_eq!(line.timestamp, Some(1234));
+    }
+
+    #[test]
+    fn negative_timestamp_parses_and_converts_pre_epoch() {
+        let lines = parse_lp("cpu usage=1 -86400000000000").unwrap();
+        let ts = lines[0].timestamp.unwrap();
+        assert_eq!(ts, -86_400_000_000_000);
+
+        let date = timestamp_to_datetime(ts);
+        assert_eq!(date.to_rfc3339(), "1969-12-31T00:00:00+00:00");
+        assert_eq!(datetime_to_timestamp(&date), ts);
+    }
+
+    #[test]
+    fn negative_timestamp_round_trips_through_serialization() {
+        let lines = parse_lp("cpu,region=west usage=1i -1").unwrap();
+        assert_eq!(line_to_lp(&lines[0]), "cpu,region=west usage=1i -1");
+    }
+
+    #[test]
+    fn malformed_lines_error_with_line_number() {
+        let err = parse_lp("cpu usage=1 100\nbogus\n").unwrap_err();
+        assert_eq!(err.line, 2);
+
+        let err = parse_lp("cpu usage=1 notanumber").unwrap_err();
+        assert!(err.message.contains("notanumber"));
+    }
+
+    fn cpu_row(host: &str, usage: &str, cores: &str, ts: &str) -> Value {
+        Value::test_record(
+            vec!["host", "usage", "cores", "ts"],
+            vec![
+                Value::test_string(host),
+                Value::test_string(usage),
+                Value::test_string(cores),
+                Value::test_string(ts),
+            ],
+        )
+    }
+
+    #[test]
+    fn csv_rows_convert_with_inferred_field_types() {
+        let rows = vec![
+            cpu_row("a", "0.5", "4", "100"),
+            cpu_row("b", "0.25", "8", "200"),
+        ];
+        let lines = rows_to_lp(
+            "cpu",
+            &["host".to_string()],
+            &["usage".to_string(), "cores".to_string()],
+            Some("ts"),
+            &rows,
+        )
+        .unwrap();
+
+        assert_eq!(
+            lines,
+            vec![
+                "cpu,host=a usage=0.5,cores=4i 100",
+                "cpu,host=b usage=0.25,cores=8i 200",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_column_reports_the_row_number() {
+        let rows = vec![
+            cpu_row("a", "0.5", "4", "100"),
+            Value::test_record(vec!["host"], vec![Value::test_string("b")]),
+        ];
+        let err = rows_to_lp("cpu", &[], &["usage".to_string()], None, &rows).unwrap_err();
+        assert!(err.starts_with("row 2:"));
+        assert!(err.contains("usage"));
+    }
+
+    #[test]
+    fn escaped_and_quoted_values_parse() {
+        let lines =
+            parse_lp(r#"disk\ io,path=/var/log msg="hello, \"world\"",free=10u"#).unwrap();
+        let line = &lines[0];
+        assert_eq!(line.measurement, "disk io");
+        assert_eq!(line.tags[0].1, "/var/log");
+        assert_eq!(
+            line.fields[0].1,
+            FieldValue::String("hello, \"world\"".to_string())
+        );
+        assert_eq!(line.fields[1].1, FieldValue::UInt(10));
+        assert_eq!(line.timestamp, None);
+    }
+}
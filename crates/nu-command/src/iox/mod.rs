@@ -0,0 +1,29 @@
+mod commands;
+mod config;
+pub mod expr;
+mod flatten;
+mod fromlp;
+mod infer;
+mod ioxtrace;
+pub mod lp;
+mod predicate;
+mod query;
+mod session;
+pub mod rewrite;
+mod tolp;
+pub mod trace;
+mod util;
+mod write;
+
+pub use commands::*;
+pub use config::*;
+pub use flatten::*;
+pub use fromlp::*;
+pub use infer::*;
+pub use ioxtrace::*;
+pub use predicate::*;
+pub use query::*;
+pub use session::*;
+pub use tolp::*;
+pub use util::*;
+pub use write::*;
@@ -0,0 +1,443 @@
+// Parsing InfluxDB-style delete/filter predicate strings, e.g.
+//   region = 'west' AND host != 'a' AND time > 100
+// into the typed Predicate shape the IOx delete API takes.
+
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Spanned,
+    SyntaxShape, Value,
+};
+
+/// A typed literal in a predicate expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Scalar {
+    Bool(bool),
+    I64(i64),
+    F64(f64),
+    String(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PredicateOp {
+    Eq,
+    NotEq,
+}
+
+impl PredicateOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PredicateOp::Eq => "=",
+            PredicateOp::NotEq => "!=",
+        }
+    }
+}
+
+/// One `column = literal` (or `!=`) conjunct.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PredicateExpr {
+    pub column: String,
+    pub op: PredicateOp,
+    pub scalar: Scalar,
+}
+
+/// A parsed predicate: an optional nanosecond time range (start inclusive,
+/// end exclusive, either side may be open) plus column conjuncts.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Predicate {
+    pub range: Option<(Option<i64>, Option<i64>)>,
+    pub exprs: Vec<PredicateExpr>,
+}
+
+/// Parse a predicate string. Conjuncts are separated by AND; `time`
+/// comparisons become the range, everything else must be an equality or
+/// inequality against a literal.
+pub fn parse_predicate(input: &str) -> Result<Predicate, String> {
+    let mut predicate = Predicate::default();
+    let mut start: Option<i64> = None;
+    let mut end: Option<i64> = None;
+
+    for conjunct in split_on_and(input) {
+        let conjunct = conjunct.trim();
+        if conjunct.is_empty() {
+            return Err("empty conjunct; is there a dangling AND?".into());
+        }
+        let (column, op, literal) = split_comparison(conjunct)?;
+
+        if column == "time" {
+            let value: i64 = match parse_scalar(literal)? {
+                Scalar::I64(v) => v,
+                other => return Err(format!("time must compare against an integer nanosecond value, found {other:?}")),
+            };
+            match op {
+                ">" => set_once(&mut start, exclusive_bound(value)?, "start")?,
+                ">=" => set_once(&mut start, value, "start")?,
+                "<" => set_once(&mut end, value, "end")?,
+                "<=" => set_once(&mut end, exclusive_bound(value)?, "end")?,
+                _ => return Err(format!("operator '{op}' is not valid for time")),
+            }
+        } else {
+            let op = match op {
+                "=" => PredicateOp::Eq,
+                "!=" => PredicateOp::NotEq,
+                _ => {
+                    return Err(format!(
+                        "operator '{op}' is not supported for column '{column}'; use = or !="
+                    ))
+                }
+            };
+            predicate.exprs.push(PredicateExpr {
+                column: column.to_string(),
+                op,
+                scalar: parse_scalar(literal)?,
+            });
+        }
+    }
+
+    if start.is_some() || end.is_some() {
+        predicate.range = Some((start, end));
+    }
+    Ok(predicate)
+}
+
+/// Build a delete predicate from an explicit nanosecond range and an
+/// expression-only predicate string, the shape the delete API takes when
+/// start/end arrive as separate arguments. The string must not carry its
+/// own `time` conjuncts — the bounds are already spoken for — and the
+/// range must be ordered.
+pub fn delete_predicate(start: i64, end: i64, predicate: &str) -> Result<Predicate, String> {
+    if start > end {
+        return Err(format!("start {start} is after end {end}"));
+    }
+    let mut parsed = if predicate.trim().is_empty() {
+        Predicate::default()
+    } else {
+        parse_predicate(predicate)?
+    };
+    if parsed.range.is_some() {
+        return Err("time bounds are given separately; remove the time conjunct".into());
+    }
+    parsed.range = Some((Some(start), Some(end)));
+    Ok(parsed)
+}
+
+/// Normalize a `>`/`<=` bound to the half-open range form by adding one
+/// nanosecond, rejecting a bound at the end of the i64 range instead of
+/// wrapping around.
+fn exclusive_bound(value: i64) -> Result<i64, String> {
+    value
+        .checked_add(1)
+        .ok_or_else(|| format!("time bound {value} is too large to make exclusive"))
+}
+
+fn set_once(slot: &mut Option<i64>, value: i64, what: &str) -> Result<(), String> {
+    if slot.replace(value).is_some() {
+        return Err(format!("duplicate time {what} bound"));
+    }
+    Ok(())
+}
+
+/// Split on the keyword AND (case-insensitive), respecting quoted strings.
+fn split_on_and(input: &str) -> Vec<String> {
+    // whitespace-tokenize outside quotes, then group on standalone ANDs
+    let mut tokens: Vec<String> = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in input.chars() {
+        if c == '\'' {
+            in_quotes = !in_quotes;
+        }
+        if c.is_whitespace() && !in_quotes {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    let mut parts = vec![];
+    let mut group: Vec<String> = vec![];
+    for token in tokens {
+        if token.eq_ignore_ascii_case("and") {
+            parts.push(group.join(" "));
+            group = vec![];
+        } else {
+            group.push(token);
+        }
+    }
+    parts.push(group.join(" "));
+    parts
+}
+
+fn split_comparison(conjunct: &str) -> Result<(&str, &str, &str), String> {
+    for op in ["!=", ">=", "<=", "=", ">", "<"] {
+        if let Some(pos) = conjunct.find(op) {
+            let column = conjunct[..pos].trim();
+            let literal = conjunct[pos + op.len()..].trim();
+            if column.is_empty() || literal.is_empty() {
+                return Err(format!("incomplete comparison '{conjunct}'"));
+            }
+            if !column.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(format!("invalid column name '{column}'"));
+            }
+            return Ok((column, op, literal));
+        }
+    }
+    Err(format!("no comparison operator in '{conjunct}'"))
+}
+
+fn parse_scalar(literal: &str) -> Result<Scalar, String> {
+    if let Some(quoted) = literal.strip_prefix('\'') {
+        return match quoted.strip_suffix('\'') {
+            Some(inner) if !inner.contains('\'') => Ok(Scalar::String(inner.to_string())),
+            _ => Err(format!("malformed string literal {literal}")),
+        };
+    }
+    match literal {
+        "true" => return Ok(Scalar::Bool(true)),
+        "false" => return Ok(Scalar::Bool(false)),
+        _ => {}
+    }
+    if let Ok(i) = literal.parse::<i64>() {
+        return Ok(Scalar::I64(i));
+    }
+    if let Ok(f) = literal.parse::<f64>() {
+        return Ok(Scalar::F64(f));
+    }
+    Err(format!("unrecognized literal '{literal}'"))
+}
+
+#[derive(Clone)]
+pub struct Ioxpredicate;
+
+impl Command for Ioxpredicate {
+    fn name(&self) -> &str {
+        "ioxpredicate"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("ioxpredicate")
+            .required(
+                "predicate",
+                SyntaxShape::String,
+                "the predicate expression to validate",
+            )
+            .category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Parse and validate an IOx predicate expression without executing it."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["influx", "delete"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let predicate: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let span = call.head;
+
+        let record = match parse_predicate(&predicate.item) {
+            Ok(parsed) => {
+                let range = match parsed.range {
+                    Some((start, end)) => Value::Record {
+                        cols: vec!["start".into(), "end".into()],
+                        vals: vec![optional_int(start, span), optional_int(end, span)],
+                        span,
+                    },
+                    None => Value::Nothing { span },
+                };
+                let exprs = parsed
+                    .exprs
+                    .iter()
+                    .map(|expr| Value::Record {
+                        cols: vec!["column".into(), "op".into(), "value".into()],
+                        vals: vec![
+                            Value::String {
+                                val: expr.column.clone(),
+                                span,
+                            },
+                            Value::String {
+                                val: expr.op.as_str().to_string(),
+                                span,
+                            },
+                            scalar_to_value(&expr.scalar, span),
+                        ],
+                        span,
+                    })
+                    .collect();
+                Value::Record {
+                    cols: vec!["valid".into(), "range".into(), "exprs".into()],
+                    vals: vec![
+                        Value::boolean(true, span),
+                        range,
+                        Value::List { vals: exprs, span },
+                    ],
+                    span,
+                }
+            }
+            Err(message) => Value::Record {
+                cols: vec!["valid".into(), "error".into()],
+                vals: vec![
+                    Value::boolean(false, span),
+                    Value::String { val: message, span },
+                ],
+                span,
+            },
+        };
+
+        Ok(record.into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Validate a delete predicate with a time range",
+                example: r#"ioxpredicate "region = 'west' AND time > 100""#,
+                result: None,
+            },
+            Example {
+                description: "An invalid predicate reports the problem instead of erroring",
+                example: r#"ioxpredicate "region ~ 'west'" | get valid"#,
+                result: None,
+            },
+        ]
+    }
+}
+
+fn optional_int(value: Option<i64>, span: nu_protocol::Span) -> Value {
+    match value {
+        Some(val) => Value::Int { val, span },
+        None => Value::Nothing { span },
+    }
+}
+
+fn scalar_to_value(scalar: &Scalar, span: nu_protocol::Span) -> Value {
+    match scalar {
+        Scalar::Bool(val) => Value::boolean(*val, span),
+        Scalar::I64(val) => Value::Int { val: *val, span },
+        Scalar::F64(val) => Value::Float { val: *val, span },
+        Scalar::String(val) => Value::String {
+            val: val.clone(),
+            span,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(Ioxpredicate {})
+    }
+
+    #[test]
+    fn parses_equality_conjuncts_and_time_range() {
+        let predicate =
+            parse_predicate("region = 'west' AND host != 'a' AND time >= 100 AND time < 200")
+                .unwrap();
+
+        assert_eq!(predicate.range, Some((Some(100), Some(200))));
+        assert_eq!(
+            predicate.exprs,
+            vec![
+                PredicateExpr {
+                    column: "region".into(),
+                    op: PredicateOp::Eq,
+                    scalar: Scalar::String("west".into()),
+                },
+                PredicateExpr {
+                    column: "host".into(),
+                    op: PredicateOp::NotEq,
+                    scalar: Scalar::String("a".into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn exclusive_time_bounds_are_normalized() {
+        let predicate = parse_predicate("time > 100 AND time <= 200").unwrap();
+        assert_eq!(predicate.range, Some((Some(101), Some(201))));
+    }
+
+    #[test]
+    fn scalar_types_are_recognized() {
+        let predicate = parse_predicate("a = 1 AND b = 1.5 AND c = true AND d = 'x'").unwrap();
+        let scalars: Vec<_> = predicate.exprs.into_iter().map(|e| e.scalar).collect();
+        assert_eq!(
+            scalars,
+            vec![
+                Scalar::I64(1),
+                Scalar::F64(1.5),
+                Scalar::Bool(true),
+                Scalar::String("x".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_predicates_are_rejected() {
+        assert!(parse_predicate("region ~ 'west'").is_err());
+        assert!(parse_predicate("region > 'west'").is_err());
+        assert!(parse_predicate("region = 'west' AND").is_err());
+        assert!(parse_predicate("time = 100").is_err());
+        assert!(parse_predicate("time > 100 AND time > 200").is_err());
+    }
+
+    #[test]
+    fn time_bound_at_i64_max_is_rejected_not_wrapped() {
+        // > and <= add a nanosecond; at i64::MAX that must be an error,
+        // not a wrap to i64::MIN
+        assert!(parse_predicate("time > 9223372036854775807").is_err());
+        assert!(parse_predicate("time <= 9223372036854775807").is_err());
+        // the inclusive forms need no adjustment and stay valid
+        assert!(parse_predicate("time >= 9223372036854775807").is_ok());
+        assert!(parse_predicate("time < 9223372036854775807").is_ok());
+    }
+
+    #[test]
+    fn delete_predicate_combines_range_and_exprs() {
+        let predicate = delete_predicate(100, 200, "region = 'west'").unwrap();
+        assert_eq!(predicate.range, Some((Some(100), Some(200))));
+        assert_eq!(predicate.exprs.len(), 1);
+
+        // an empty string is a pure range delete
+        let predicate = delete_predicate(100, 200, "").unwrap();
+        assert_eq!(predicate, Predicate {
+            range: Some((Some(100), Some(200))),
+            exprs: vec![],
+        });
+    }
+
+    #[test]
+    fn delete_predicate_rejects_bad_input() {
+        assert!(delete_predicate(200, 100, "").is_err());
+        assert!(delete_predicate(100, 200, "time > 50").is_err());
+        assert!(delete_predicate(100, 200, "region ~ 'west'").is_err());
+    }
+
+    #[test]
+    fn quoted_and_is_not_a_separator() {
+        let predicate = parse_predicate("msg = 'fish and chips'").unwrap();
+        assert_eq!(
+            predicate.exprs[0].scalar,
+            Scalar::String("fish and chips".into())
+        );
+    }
+}
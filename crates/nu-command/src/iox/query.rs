@@ -0,0 +1,742 @@
+use chrono::{DateTime, TimeZone, Utc};
+use nu_protocol::{ShellError, Span};
+
+// DataFusion session configuration keys that IOx is known to accept. Settings
+// outside this list are still forwarded (newer servers may understand them),
+// but the caller is handed a warning for each so typos don't go unnoticed.
+const KNOWN_SESSION_KEYS: &[&str] = &[
+    "target_partitions",
+    "batch_size",
+    "coalesce_batches",
+    "collect_statistics",
+    "parquet_pruning",
+    "repartition_aggregations",
+    "repartition_joins",
+    "repartition_windows",
+];
+
+/// A set of DataFusion session settings to forward with a query, collected
+/// from repeated `--set key=value` flags.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionConfig {
+    settings: Vec<(String, String)>,
+}
+
+impl SessionConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a list of `key=value` entries as given on the command line.
+    /// Returns the config plus one warning message per unknown key.
+    pub fn from_entries(
+        entries: &[(String, Span)],
+    ) -> Result<(Self, Vec<String>), ShellError> {
+        let mut config = Self::new();
+        let mut warnings = vec![];
+
+        for (entry, span) in entries {
+            let (key, value) = parse_session_entry(entry, *span)?;
+            if !is_known_session_key(&key) {
+                warnings.push(format!(
+                    "unknown session setting '{key}'; forwarding it to the server anyway"
+                ));
+            }
+            config.set(key, value);
+        }
+
+        Ok((config, warnings))
+    }
+
+    /// Set a single setting, replacing any earlier value for the same key.
+    pub fn set(&mut self, key: String, value: String) {
+        if let Some(existing) = self.settings.iter_mut().find(|(k, _)| k == &key) {
+            existing.1 = value;
+        } else {
+            self.settings.push((key, value));
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.settings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn settings(&self) -> &[(String, String)] {
+        &self.settings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+    }
+}
+
+/// Is this a session key IOx/DataFusion documents?
+pub fn is_known_session_key(key: &str) -> bool {
+    KNOWN_SESSION_KEYS.contains(&key)
+}
+
+/// Split a `key=value` command-line entry, rejecting malformed ones.
+fn parse_session_entry(entry: &str, span: Span) -> Result<(String, String), ShellError> {
+    match entry.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(ShellError::GenericError(
+            format!("invalid session setting '{entry}'"),
+            "expected key=value".into(),
+            Some(span),
+            None,
+            Vec::new(),
+        )),
+    }
+}
+
+/// The language a query is written in. IOx accepts both SQL and InfluxQL
+/// over the same Flight endpoint; the ticket field used differs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QueryLanguage {
+    #[default]
+    Sql,
+    InfluxQl,
+}
+
+impl QueryLanguage {
+    /// Parse the value of a `--lang` flag.
+    pub fn from_flag(value: &str, span: Span) -> Result<Self, ShellError> {
+        match value.to_ascii_lowercase().as_str() {
+            "sql" => Ok(QueryLanguage::Sql),
+            "influxql" => Ok(QueryLanguage::InfluxQl),
+            _ => Err(ShellError::GenericError(
+                format!("unknown query language '{value}'"),
+                "expected 'sql' or 'influxql'".into(),
+                Some(span),
+                None,
+                Vec::new(),
+            )),
+        }
+    }
+
+    /// The ticket field the query text is carried in.
+    fn ticket_field(&self) -> &'static str {
+        match self {
+            QueryLanguage::Sql => "sql_query",
+            QueryLanguage::InfluxQl => "influxql_query",
+        }
+    }
+}
+
+/// The pieces of an IOx Flight query before it is sent: the target database,
+/// the query text and its language, and any session settings to apply for
+/// its execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryRequest {
+    db_name: String,
+    query: String,
+    language: QueryLanguage,
+    session_config: SessionConfig,
+    schema_only: bool,
+}
+
+impl QueryRequest {
+    pub fn new(db_name: impl Into<String>, query: impl Into<String>) -> Self {
+        QueryRequest {
+            db_name: db_name.into(),
+            query: query.into(),
+            language: QueryLanguage::default(),
+            session_config: SessionConfig::new(),
+            schema_only: false,
+        }
+    }
+
+    pub fn with_language(mut self, language: QueryLanguage) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub fn with_session_config(mut self, session_config: SessionConfig) -> Self {
+        self.session_config = session_config;
+        self
+    }
+
+    /// Only the result schema is wanted (`--schema-only`): the client
+    /// should stop consuming the Flight stream after the schema message
+    /// instead of pulling data batches. The ticket is unchanged — this is
+    /// purely client-side behavior, cheaper than a `LIMIT 0` round-trip.
+    pub fn with_schema_only(mut self, schema_only: bool) -> Self {
+        self.schema_only = schema_only;
+        self
+    }
+
+    pub fn schema_only(&self) -> bool {
+        self.schema_only
+    }
+
+    pub fn db_name(&self) -> &str {
+        &self.db_name
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn language(&self) -> QueryLanguage {
+        self.language
+    }
+
+    pub fn session_config(&self) -> &SessionConfig {
+        &self.session_config
+    }
+
+    /// Render the JSON ticket that goes into the Flight `DoGet` call. Session
+    /// settings ride along under a `session_config` object when present.
+    pub fn ticket(&self) -> String {
+        let mut ticket = nu_json::Map::new();
+        ticket.insert(
+            "database_name".to_string(),
+            nu_json::Value::String(self.db_name.clone()),
+        );
+        ticket.insert(
+            self.language.ticket_field().to_string(),
+            nu_json::Value::String(self.query.clone()),
+        );
+
+        if !self.session_config.is_empty() {
+            let mut settings = nu_json::Map::new();
+            for (key, value) in self.session_config.settings() {
+                settings.insert(key.clone(), nu_json::Value::String(value.clone()));
+            }
+            ticket.insert(
+                "session_config".to_string(),
+                nu_json::Value::Object(settings),
+            );
+        }
+
+        nu_json::to_string_raw(&nu_json::Value::Object(ticket))
+            .expect("json serialization of a string map cannot fail")
+    }
+}
+
+/// One end of a `--since`/`--until` time filter.
+///
+/// Accepts `now`, a relative duration such as `1h`, `30m` or `1h30m`
+/// (interpreted as that long ago), or an absolute RFC 3339 date/time
+/// (`2023-01-01` or `2023-01-01T12:00:00Z`).
+pub fn parse_time_bound(
+    raw: &str,
+    now: DateTime<Utc>,
+    span: Span,
+) -> Result<DateTime<Utc>, ShellError> {
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("now") {
+        return Ok(now);
+    }
+    if let Some(ago) = super::util::duration_from_str(raw) {
+        return Ok(now - ago);
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        if let Some(midnight) = date.and_hms_opt(0, 0, 0) {
+            return Ok(Utc.from_utc_datetime(&midnight));
+        }
+    }
+
+    Err(ShellError::GenericError(
+        format!("unable to parse '{raw}' as a time bound"),
+        "expected 'now', a duration like 1h30m, or an RFC 3339 date".into(),
+        Some(span),
+        None,
+        Vec::new(),
+    ))
+}
+
+/// Lowercased word tokens of a query with their byte offsets. Words break
+/// on anything that isn't alphanumeric or `_`, so newlines count as
+/// separators, and quoted strings are opaque. Both time-filter helpers
+/// look at the query through this so an identifier like `rate_limit` or a
+/// literal `'no limit'` can't be mistaken for a keyword.
+fn keyword_tokens(query: &str) -> Vec<(usize, String)> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut start = 0;
+    let mut quote: Option<char> = None;
+
+    for (pos, c) in query.char_indices() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        if c == '\'' || c == '"' {
+            quote = Some(c);
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            if current.is_empty() {
+                start = pos;
+            }
+            current.push(c.to_ascii_lowercase());
+            continue;
+        }
+        if !current.is_empty() {
+            tokens.push((start, std::mem::take(&mut current)));
+        }
+    }
+    if !current.is_empty() {
+        tokens.push((start, current));
+    }
+    tokens
+}
+
+/// Does this query already constrain `time`? Used to avoid injecting a
+/// second filter on top of one the user wrote.
+pub fn query_filters_time(query: &str) -> bool {
+    let tokens = keyword_tokens(query);
+    match tokens.iter().position(|(_, token)| token == "where") {
+        Some(pos) => tokens[pos..].iter().any(|(_, token)| token == "time"),
+        None => false,
+    }
+}
+
+/// Append a `time >= .. AND time <= ..` clause to `query`.
+///
+/// The clause is inserted before any trailing GROUP BY/ORDER BY/LIMIT/OFFSET
+/// so the common interactive shapes keep working. Callers should check
+/// [`query_filters_time`] first and warn-and-skip when it returns true.
+pub fn inject_time_filter(
+    query: &str,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> String {
+    let mut conditions = vec![];
+    if let Some(since) = since {
+        conditions.push(format!("time >= '{}'", since.to_rfc3339()));
+    }
+    if let Some(until) = until {
+        conditions.push(format!("time <= '{}'", until.to_rfc3339()));
+    }
+    if conditions.is_empty() {
+        return query.to_string();
+    }
+    let conditions = conditions.join(" AND ");
+
+    let tokens = keyword_tokens(query);
+    let mut tail_start = query.len();
+    for (i, (pos, token)) in tokens.iter().enumerate() {
+        let starts_tail = match token.as_str() {
+            "limit" | "offset" => true,
+            "group" | "order" => {
+                matches!(tokens.get(i + 1), Some((_, next)) if next == "by")
+            }
+            _ => false,
+        };
+        if starts_tail {
+            tail_start = *pos;
+            break;
+        }
+    }
+    let (head, tail) = query.split_at(tail_start);
+
+    let connective = if tokens
+        .iter()
+        .take_while(|(pos, _)| *pos < tail_start)
+        .any(|(_, token)| token == "where")
+    {
+        "AND"
+    } else {
+        "WHERE"
+    };
+
+    format!("{} {connective} {conditions} {}", head.trim_end(), tail.trim_start())
+        .trim_end()
+        .to_string()
+}
+
+/// Build a `SELECT` from the `--table`/`--select`/`--limit` convenience
+/// flags, so "show me table X" doesn't require writing SQL. Returns
+/// `Ok(None)` when `--table` wasn't given (the positional query applies),
+/// and errors if both a positional query and `--table` are present, since
+/// silently preferring one would be surprising. Time bounds compose via
+/// [`inject_time_filter`] on the generated query like any other.
+pub fn build_query_from_flags(
+    table: Option<&str>,
+    select: Option<&str>,
+    limit: Option<i64>,
+    positional_query: Option<&str>,
+    span: Span,
+) -> Result<Option<String>, ShellError> {
+    let Some(table) = table else {
+        return Ok(None);
+    };
+    if positional_query.is_some() {
+        return Err(ShellError::GenericError(
+            "--table cannot be combined with a query".into(),
+            "either write the SQL yourself or let --table generate it".into(),
+            Some(span),
+            None,
+            Vec::new(),
+        ));
+    }
+
+    let mut query = format!("SELECT {} FROM {table}", select.unwrap_or("*"));
+    if let Some(limit) = limit {
+        query.push_str(&format!(" LIMIT {limit}"));
+    }
+    Ok(Some(query))
+}
+
+/// One node of an explained plan, with the cost estimates DataFusion
+/// attaches when statistics are available.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExplainNode {
+    pub node: String,
+    pub estimated_rows: Option<u64>,
+    pub estimated_bytes: Option<u64>,
+}
+
+/// Parse an explain plan string into one record per node, pulling out the
+/// row/byte estimates where the server included them. DataFusion renders
+/// estimates either as `statistics=[Rows=Exact(8), Bytes=Exact(640)]` on
+/// physical nodes or as plain `rows=8, bytes=640` annotations; nodes
+/// without estimates get `None`s so the plan structure is still visible.
+pub fn parse_explain_cost(plan: &str) -> Vec<ExplainNode> {
+    plan.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let node = trimmed
+                .split(':')
+                .next()
+                .unwrap_or(trimmed)
+                .trim()
+                .to_string();
+            ExplainNode {
+                node,
+                estimated_rows: parse_estimate(trimmed, "rows"),
+                estimated_bytes: parse_estimate(trimmed, "bytes"),
+            }
+        })
+        .collect()
+}
+
+/// Find `key=N` or `Key=Exact(N)` (case-insensitive key) in an explain line.
+fn parse_estimate(line: &str, key: &str) -> Option<u64> {
+    let lower = line.to_ascii_lowercase();
+    let pos = lower.find(&format!("{key}="))?;
+    let rest = &line[pos + key.len() + 1..];
+    let rest = rest
+        .strip_prefix("Exact(")
+        .or_else(|| rest.strip_prefix("Inexact("))
+        .unwrap_or(rest);
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(s: &str) -> (String, Span) {
+        (s.to_string(), Span::test_data())
+    }
+
+    #[test]
+    fn schema_only_does_not_change_the_ticket() {
+        let request = QueryRequest::new("mydb", "select 1");
+        let plain_ticket = request.ticket();
+
+        let request = request.with_schema_only(true);
+        assert!(request.schema_only());
+        assert_eq!(request.ticket(), plain_ticket);
+    }
+
+    #[test]
+    fn table_flag_generates_a_select_star() {
+        let query =
+            build_query_from_flags(Some("cpu"), None, None, None, Span::test_data()).unwrap();
+        assert_eq!(query.as_deref(), Some("SELECT * FROM cpu"));
+    }
+
+    #[test]
+    fn select_and_limit_flags_compose() {
+        let query = build_query_from_flags(
+            Some("cpu"),
+            Some("host, usage"),
+            Some(10),
+            None,
+            Span::test_data(),
+        )
+        .unwrap();
+        assert_eq!(query.as_deref(), Some("SELECT host, usage FROM cpu LIMIT 10"));
+    }
+
+    #[test]
+    fn table_flag_composes_with_time_filters() {
+        let query = build_query_from_flags(Some("cpu"), None, Some(10), None, Span::test_data())
+            .unwrap()
+            .unwrap();
+        let since = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            inject_time_filter(&query, Some(since), None),
+            "SELECT * FROM cpu WHERE time >= '2023-01-01T00:00:00+00:00' LIMIT 10"
+        );
+    }
+
+    #[test]
+    fn no_table_flag_defers_to_the_positional_query() {
+        let query =
+            build_query_from_flags(None, None, Some(10), Some("select 1"), Span::test_data())
+                .unwrap();
+        assert_eq!(query, None);
+    }
+
+    #[test]
+    fn table_flag_with_positional_query_is_an_error() {
+        assert!(
+            build_query_from_flags(Some("cpu"), None, None, Some("select 1"), Span::test_data())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn explain_cost_annotations_become_structured_nodes() {
+        let plan = "\
+ProjectionExec: expr=[usage], statistics=[Rows=Exact(8), Bytes=Exact(640)]
+  FilterExec: time@2 > 100, rows=120, bytes=9600
+    ParquetExec: file_groups={1 group}";
+
+        let nodes = parse_explain_cost(plan);
+        assert_eq!(
+            nodes,
+            vec![
+                ExplainNode {
+                    node: "ProjectionExec".into(),
+                    estimated_rows: Some(8),
+                    estimated_bytes: Some(640),
+                },
+                ExplainNode {
+                    node: "FilterExec".into(),
+                    estimated_rows: Some(120),
+                    estimated_bytes: Some(9600),
+                },
+                ExplainNode {
+                    node: "ParquetExec".into(),
+                    estimated_rows: None,
+                    estimated_bytes: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_known_settings_without_warnings() {
+        let (config, warnings) = SessionConfig::from_entries(&[
+            entry("target_partitions=4"),
+            entry("batch_size=1024"),
+        ])
+        .unwrap();
+
+        assert!(warnings.is_empty());
+        assert_eq!(config.get("target_partitions"), Some("4"));
+        assert_eq!(config.get("batch_size"), Some("1024"));
+    }
+
+    #[test]
+    fn warns_on_unknown_key_but_keeps_it() {
+        let (config, warnings) =
+            SessionConfig::from_entries(&[entry("tarket_partitions=4")]).unwrap();
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("tarket_partitions"));
+        assert_eq!(config.get("tarket_partitions"), Some("4"));
+    }
+
+    #[test]
+    fn rejects_malformed_entry() {
+        assert!(SessionConfig::from_entries(&[entry("batch_size")]).is_err());
+        assert!(SessionConfig::from_entries(&[entry("=4")]).is_err());
+    }
+
+    #[test]
+    fn later_set_overrides_earlier() {
+        let (config, _) = SessionConfig::from_entries(&[
+            entry("batch_size=512"),
+            entry("batch_size=2048"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.get("batch_size"), Some("2048"));
+        assert_eq!(config.settings().len(), 1);
+    }
+
+    #[test]
+    fn ticket_includes_session_settings() {
+        let (config, _) =
+            SessionConfig::from_entries(&[entry("target_partitions=4")]).unwrap();
+        let request = QueryRequest::new("mydb", "select * from cpu")
+            .with_session_config(config);
+
+        let ticket: nu_json::Value = nu_json::from_str(&request.ticket()).unwrap();
+        assert_eq!(
+            ticket.find("database_name"),
+            Some(&nu_json::Value::String("mydb".into()))
+        );
+        assert_eq!(
+            ticket
+                .find("session_config")
+                .and_then(|s| s.find("target_partitions")),
+            Some(&nu_json::Value::String("4".into()))
+        );
+    }
+
+    #[test]
+    fn sql_query_populates_sql_field() {
+        let request = QueryRequest::new("mydb", "select * from cpu");
+        let ticket: nu_json::Value = nu_json::from_str(&request.ticket()).unwrap();
+
+        assert_eq!(
+            ticket.find("sql_query"),
+            Some(&nu_json::Value::String("select * from cpu".into()))
+        );
+        assert!(ticket.find("influxql_query").is_none());
+    }
+
+    #[test]
+    fn influxql_query_populates_influxql_field() {
+        let request = QueryRequest::new("mydb", "show measurements")
+            .with_language(QueryLanguage::InfluxQl);
+        let ticket: nu_json::Value = nu_json::from_str(&request.ticket()).unwrap();
+
+        assert_eq!(
+            ticket.find("influxql_query"),
+            Some(&nu_json::Value::String("show measurements".into()))
+        );
+        assert!(ticket.find("sql_query").is_none());
+    }
+
+    #[test]
+    fn language_flag_parsing() {
+        let span = Span::test_data();
+        assert_eq!(
+            QueryLanguage::from_flag("sql", span).unwrap(),
+            QueryLanguage::Sql
+        );
+        assert_eq!(
+            QueryLanguage::from_flag("InfluxQL", span).unwrap(),
+            QueryLanguage::InfluxQl
+        );
+        assert!(QueryLanguage::from_flag("flux", span).is_err());
+    }
+
+    #[test]
+    fn ticket_omits_empty_session_config() {
+        let request = QueryRequest::new("mydb", "select 1");
+        let ticket: nu_json::Value = nu_json::from_str(&request.ticket()).unwrap();
+        assert!(ticket.find("session_config").is_none());
+    }
+
+    fn test_now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 1, 15, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn relative_time_bounds() {
+        let span = Span::test_data();
+        assert_eq!(
+            parse_time_bound("1h", test_now(), span).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 15, 11, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_time_bound("1h30m", test_now(), span).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 15, 10, 30, 0).unwrap()
+        );
+        assert_eq!(parse_time_bound("now", test_now(), span).unwrap(), test_now());
+    }
+
+    #[test]
+    fn absolute_time_bounds() {
+        let span = Span::test_data();
+        assert_eq!(
+            parse_time_bound("2023-01-01", test_now(), span).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_time_bound("2023-01-01T06:30:00Z", test_now(), span).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 1, 6, 30, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn unparseable_time_bound_errors() {
+        assert!(parse_time_bound("yesterday-ish", test_now(), Span::test_data()).is_err());
+        assert!(parse_time_bound("5", test_now(), Span::test_data()).is_err());
+    }
+
+    #[test]
+    fn injects_where_clause_when_none_present() {
+        let out = inject_time_filter("select * from cpu", Some(test_now()), None);
+        assert_eq!(
+            out,
+            "select * from cpu WHERE time >= '2023-01-15T12:00:00+00:00'"
+        );
+    }
+
+    #[test]
+    fn appends_to_existing_where_clause() {
+        let out = inject_time_filter(
+            "select * from cpu where region = 'west'",
+            None,
+            Some(test_now()),
+        );
+        assert_eq!(
+            out,
+            "select * from cpu where region = 'west' AND time <= '2023-01-15T12:00:00+00:00'"
+        );
+    }
+
+    #[test]
+    fn injects_before_trailing_clauses() {
+        let out = inject_time_filter("select * from cpu limit 10", Some(test_now()), None);
+        assert_eq!(
+            out,
+            "select * from cpu WHERE time >= '2023-01-15T12:00:00+00:00' limit 10"
+        );
+    }
+
+    #[test]
+    fn detects_existing_time_filter() {
+        assert!(query_filters_time("select * from cpu where time > now() - 1h"));
+        assert!(!query_filters_time("select * from cpu where region = 'west'"));
+        assert!(!query_filters_time("select time from cpu"));
+    }
+
+    #[test]
+    fn detects_time_filter_across_newlines() {
+        assert!(query_filters_time("select *\nfrom cpu\nwhere time > 5"));
+        assert!(query_filters_time("select * from cpu WHERE\n\ttime > 5"));
+    }
+
+    #[test]
+    fn embedded_keywords_do_not_start_the_tail() {
+        // `rate_limit` is an identifier, not a LIMIT clause
+        let out = inject_time_filter("select rate_limit from cpu", Some(test_now()), None);
+        assert_eq!(
+            out,
+            "select rate_limit from cpu WHERE time >= '2023-01-15T12:00:00+00:00'"
+        );
+
+        // a quoted 'no limit' is data, and the existing WHERE is still seen
+        let out = inject_time_filter(
+            "select * from cpu where msg = 'no limit'",
+            Some(test_now()),
+            None,
+        );
+        assert_eq!(
+            out,
+            "select * from cpu where msg = 'no limit' AND time >= '2023-01-15T12:00:00+00:00'"
+        );
+    }
+}
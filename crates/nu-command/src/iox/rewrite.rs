@@ -0,0 +1,593 @@
+// Rewrites that simplify predicate expressions before they are handed to
+// IOx, mirroring the shapes its own planner produces.
+
+use super::expr::*;
+
+/// General expression simplifications (cast removal, constant folding).
+#[derive(Debug, Default)]
+pub struct IOxExprRewriter {}
+
+impl IOxExprRewriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ExprRewriter for IOxExprRewriter {
+    fn mutate(&mut self, expr: Expr) -> Expr {
+        match expr {
+            Expr::BinaryExpr { left, op, right } if op.is_comparison() => {
+                if is_case(&left) {
+                    inline_case(*left, |then| binary_expr(then, op, (*right).clone()))
+                } else if is_case(&right) {
+                    inline_case(*right, |then| binary_expr((*left).clone(), op, then))
+                } else {
+                    unwrap_cast_in_comparison(*left, op, *right)
+                }
+            }
+            Expr::BinaryExpr {
+                left,
+                op: Operator::StringConcat,
+                right,
+            } => fold_string_concat(*left, *right),
+            Expr::Cast { expr, data_type } => fold_literal_cast(*expr, data_type),
+            expr => expr,
+        }
+    }
+}
+
+/// Entry point: apply [`IOxExprRewriter`] over the whole tree.
+pub fn rewrite(expr: Expr) -> Expr {
+    rewrite_expr(expr, &mut IOxExprRewriter::new())
+}
+
+/// Predicate-level simplifications: drops null checks other conjuncts make
+/// redundant.
+#[derive(Debug, Default)]
+pub struct IOxPredicateRewriter {}
+
+impl IOxPredicateRewriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ExprRewriter for IOxPredicateRewriter {
+    fn mutate(&mut self, expr: Expr) -> Expr {
+        match expr {
+            Expr::BinaryExpr {
+                left,
+                op: Operator::And,
+                right,
+            } => simplify_redundant_not_null(*left, *right),
+            Expr::BinaryExpr {
+                left,
+                op: Operator::Or,
+                right,
+            } => simplify_absorbed_comparison(*left, *right),
+            expr => expr,
+        }
+    }
+}
+
+/// Entry point: apply [`IOxPredicateRewriter`] over the filter's AND/OR
+/// structure.
+pub fn rewrite_predicate(expr: Expr) -> Expr {
+    rewrite_predicate_with(expr, RewriteOrder::TopDown)
+}
+
+/// [`rewrite_predicate`] with an explicit traversal order. The conjunct
+/// rewrites only look one level down, so nested redundancy such as
+/// `NOT(x IS NULL) AND (NOT(x IS NULL) AND x = 5)` collapses fully only
+/// bottom-up, where the inner AND simplifies before its parent is visited.
+pub fn rewrite_predicate_with(expr: Expr, order: RewriteOrder) -> Expr {
+    rewrite_filter_context(expr, &mut IOxPredicateRewriter::new(), order)
+}
+
+/// Apply a predicate rewriter over the AND/OR skeleton of a filter only.
+///
+/// The null-check simplifications are proven for filter semantics, where a
+/// row evaluating to NULL is dropped just like false. That equivalence
+/// flips under negation: `NOT(x IS NOT NULL AND x = 5)` is true for a NULL
+/// `x` (the row is kept), but rewriting the conjunction underneath would
+/// leave `NOT(x = 5)`, which is NULL — dropped. So unlike
+/// [`rewrite_expr_with`], this traversal descends only through AND/OR
+/// nodes, where filter semantics still hold, and leaves every other
+/// subtree (NOT, IS NULL, CASE, comparison operands) untouched.
+fn rewrite_filter_context(
+    expr: Expr,
+    rewriter: &mut impl ExprRewriter,
+    order: RewriteOrder,
+) -> Expr {
+    if !matches!(
+        &expr,
+        Expr::BinaryExpr {
+            op: Operator::And | Operator::Or,
+            ..
+        }
+    ) {
+        return expr;
+    }
+    match order {
+        RewriteOrder::TopDown => {
+            let expr = rewriter.mutate(expr);
+            map_children(expr, |child| rewrite_filter_context(child, rewriter, order))
+        }
+        RewriteOrder::BottomUp => {
+            let expr = map_children(expr, |child| rewrite_filter_context(child, rewriter, order));
+            rewriter.mutate(expr)
+        }
+    }
+}
+
+/// `col IS NOT NULL AND col <cmp> lit` keeps only the comparison, for any
+/// comparison operator and a non-null literal.
+///
+/// Why this preserves nulls: under SQL three-valued logic, when `col` is
+/// NULL every comparison against a non-null literal evaluates to NULL, and
+/// `NULL AND x` is never true, so the row is filtered out — exactly what
+/// the dropped `IS NOT NULL` (false for that row) did. When `col` is not
+/// null the dropped conjunct is true and contributes nothing. A predicate
+/// only keeps rows that evaluate to true, so both forms select the same
+/// rows.
+fn simplify_redundant_not_null(left: Expr, right: Expr) -> Expr {
+    if let Some(column) = not_null_check_column(&left) {
+        if comparison_column(&right) == Some(column) {
+            return right;
+        }
+    }
+    if let Some(column) = not_null_check_column(&right) {
+        if comparison_column(&left) == Some(column) {
+            return left;
+        }
+    }
+    binary_expr(left, Operator::And, right)
+}
+
+/// `col IS NOT NULL OR col <cmp> lit` keeps only the null check, for any
+/// comparison operator and a non-null literal.
+///
+/// Why this is safe under filter semantics (rows evaluating to NULL or
+/// false are dropped): when `col` is NULL the null check is false and the
+/// comparison is NULL, so `false OR NULL` is NULL — dropped, same as the
+/// plain null check. When `col` is not null the null check is already true
+/// and the comparison cannot add rows. The comparison arm is absorbed.
+///
+/// The superficially similar `col IS NULL OR col <cmp> lit` must be left
+/// alone: it keeps both the NULL rows and the matching rows, and neither
+/// disjunct alone selects that set.
+fn simplify_absorbed_comparison(left: Expr, right: Expr) -> Expr {
+    if let Some(column) = not_null_check_column(&left) {
+        if comparison_column(&right) == Some(column) {
+            return left;
+        }
+    }
+    if let Some(column) = not_null_check_column(&right) {
+        if comparison_column(&left) == Some(column) {
+            return right;
+        }
+    }
+    binary_expr(left, Operator::Or, right)
+}
+
+/// The column a `col IS NOT NULL` (or the equivalent `NOT(col IS NULL)`)
+/// check applies to.
+fn not_null_check_column(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::IsNotNull(inner) => column_name(inner),
+        Expr::Not(inner) => match inner.as_ref() {
+            Expr::IsNull(inner) => column_name(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The column of a `col <cmp> literal` (either side) with a non-null
+/// literal, for null-rejecting comparison operators.
+fn comparison_column(expr: &Expr) -> Option<&str> {
+    let Expr::BinaryExpr { left, op, right } = expr else {
+        return None;
+    };
+    if !op.is_comparison() {
+        return None;
+    }
+    match (left.as_ref(), right.as_ref()) {
+        (column, Expr::Literal(lit)) | (Expr::Literal(lit), column) if !lit.is_null() => {
+            column_name(column)
+        }
+        _ => None,
+    }
+}
+
+fn column_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Column(name) => Some(name),
+        _ => None,
+    }
+}
+
+// The largest integer magnitude a f64 represents exactly. Casting an i64 in
+// this range to f64 is lossless, so a comparison can cross it unchanged.
+const F64_EXACT_INT: f64 = 9_007_199_254_740_992.0; // 2^53
+
+/// `CAST(col AS FLOAT) <op> float-literal` appears when the planner widens
+/// an integer field column. When the literal is exactly an integer the cast
+/// is value-preserving for every comparison operator (i64 -> f64 is an
+/// order-preserving injection within 2^53), so compare natively instead and
+/// let IOx push the predicate down to the column.
+fn unwrap_cast_in_comparison(left: Expr, op: Operator, right: Expr) -> Expr {
+    let (left, right) = match (left, right) {
+        (
+            Expr::Cast {
+                expr: inner,
+                data_type: DataType::Float64,
+            },
+            Expr::Literal(ScalarValue::Float64(Some(f))),
+        ) if exact_int(f) => (*inner, lit_int(f as i64)),
+        (
+            Expr::Literal(ScalarValue::Float64(Some(f))),
+            Expr::Cast {
+                expr: inner,
+                data_type: DataType::Float64,
+            },
+        ) if exact_int(f) => (lit_int(f as i64), *inner),
+        (left, right) => (left, right),
+    };
+    binary_expr(left, op, right)
+}
+
+fn exact_int(f: f64) -> bool {
+    f.fract() == 0.0 && f.abs() < F64_EXACT_INT
+}
+
+fn is_case(expr: &Expr) -> bool {
+    matches!(expr, Expr::Case { .. })
+}
+
+/// Push a comparison against a CASE into every branch:
+/// `CASE WHEN w THEN t ELSE e END <op> lit` becomes
+/// `CASE WHEN w THEN t <op> lit ELSE e <op> lit END`, exposing literal
+/// comparisons the other rewrites can fold. The expr form works the same
+/// way — the discriminant only selects which branch runs, so it is kept
+/// as-is and the comparison applies to each branch result. A missing ELSE
+/// yields NULL, and `NULL <op> lit` is NULL again, so it stays missing.
+fn inline_case(case: Expr, compare: impl Fn(Expr) -> Expr) -> Expr {
+    match case {
+        Expr::Case {
+            expr,
+            when_then,
+            else_expr,
+        } => Expr::Case {
+            expr,
+            when_then: when_then
+                .into_iter()
+                .map(|(when, then)| (when, compare(then)))
+                .collect(),
+            else_expr: else_expr.map(|e| Box::new(compare(*e))),
+        },
+        other => other,
+    }
+}
+
+/// `lit('a') || lit('b')` => `lit('ab')`. Concatenated literals show up
+/// after other rewrites have folded their operands. Only fires for two
+/// non-null string literals: a NULL operand makes the whole concat NULL,
+/// which folding to a string would change.
+fn fold_string_concat(left: Expr, right: Expr) -> Expr {
+    if let (
+        Expr::Literal(ScalarValue::Utf8(Some(a))),
+        Expr::Literal(ScalarValue::Utf8(Some(b))),
+    ) = (&left, &right)
+    {
+        return lit_str(format!("{a}{b}"));
+    }
+    binary_expr(left, Operator::StringConcat, right)
+}
+
+/// Fold a cast applied directly to a literal when the conversion is
+/// provably lossless; anything else is left for the server to evaluate.
+fn fold_literal_cast(expr: Expr, data_type: DataType) -> Expr {
+    if let Expr::Literal(value) = &expr {
+        match (value, data_type) {
+            (ScalarValue::Int64(Some(i)), DataType::Float64)
+                if i.unsigned_abs() < F64_EXACT_INT as u64 =>
+            {
+                return lit_float(*i as f64);
+            }
+            (ScalarValue::Int64(Some(i)), DataType::Utf8) => {
+                return lit_str(i.to_string());
+            }
+            (ScalarValue::Utf8(Some(s)), DataType::Int64) => {
+                if let Ok(i) = s.trim().parse::<i64>() {
+                    return lit_int(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    expr.cast(data_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_cast_comparison_is_unwrapped() {
+        // CAST(usage AS FLOAT) > 5.0  =>  usage > 5
+        let expr = binary_expr(
+            col("usage").cast(DataType::Float64),
+            Operator::Gt,
+            lit_float(5.0),
+        );
+        assert_eq!(rewrite(expr), binary_expr(col("usage"), Operator::Gt, lit_int(5)));
+    }
+
+    #[test]
+    fn safe_cast_comparison_literal_on_left() {
+        let expr = binary_expr(
+            lit_float(5.0),
+            Operator::Eq,
+            col("usage").cast(DataType::Float64),
+        );
+        assert_eq!(rewrite(expr), binary_expr(lit_int(5), Operator::Eq, col("usage")));
+    }
+
+    #[test]
+    fn non_integral_literal_leaves_cast_untouched() {
+        // 5.5 is not exactly an integer; removing the cast would change the
+        // comparison, so nothing fires.
+        let expr = binary_expr(
+            col("usage").cast(DataType::Float64),
+            Operator::Eq,
+            lit_float(5.5),
+        );
+        assert_eq!(rewrite(expr.clone()), expr);
+    }
+
+    #[test]
+    fn literal_casts_fold_when_lossless() {
+        assert_eq!(rewrite(lit_int(5).cast(DataType::Float64)), lit_float(5.0));
+        assert_eq!(rewrite(lit_int(42).cast(DataType::Utf8)), lit_str("42"));
+        assert_eq!(rewrite(lit_str("17").cast(DataType::Int64)), lit_int(17));
+    }
+
+    #[test]
+    fn lossy_literal_casts_are_left_alone() {
+        // 2^53 + 1 is not representable as f64.
+        let big = lit_int(9_007_199_254_740_993);
+        assert_eq!(
+            rewrite(big.clone().cast(DataType::Float64)),
+            big.cast(DataType::Float64)
+        );
+        let not_a_number = lit_str("west");
+        assert_eq!(
+            rewrite(not_a_number.clone().cast(DataType::Int64)),
+            not_a_number.cast(DataType::Int64)
+        );
+    }
+
+    #[test]
+    fn cast_on_column_alone_is_untouched() {
+        let expr = col("usage").cast(DataType::Float64);
+        assert_eq!(rewrite(expr.clone()), expr);
+    }
+
+    #[test]
+    fn literal_string_concat_folds() {
+        let expr = binary_expr(lit_str("a"), Operator::StringConcat, lit_str("b"));
+        assert_eq!(rewrite(expr), lit_str("ab"));
+    }
+
+    #[test]
+    fn non_literal_or_null_concat_is_untouched() {
+        let expr = binary_expr(col("host"), Operator::StringConcat, lit_str("b"));
+        assert_eq!(rewrite(expr.clone()), expr);
+
+        let null = Expr::Literal(ScalarValue::Utf8(None));
+        let expr = binary_expr(lit_str("a"), Operator::StringConcat, null);
+        assert_eq!(rewrite(expr.clone()), expr);
+    }
+
+    #[test]
+    fn fold_case_basic() {
+        // CASE WHEN host IS NULL THEN 'none' ELSE host END = 'west'
+        // pushes the comparison into both branches.
+        let case = Expr::Case {
+            expr: None,
+            when_then: vec![(col("host").is_null(), lit_str("none"))],
+            else_expr: Some(Box::new(col("host"))),
+        };
+        let expected = Expr::Case {
+            expr: None,
+            when_then: vec![(col("host").is_null(), lit_str("none").eq(lit_str("west")))],
+            else_expr: Some(Box::new(col("host").eq(lit_str("west")))),
+        };
+        assert_eq!(rewrite(case.eq(lit_str("west"))), expected);
+    }
+
+    #[test]
+    fn fold_case_expr_form() {
+        // CASE host WHEN 'a' THEN 1 ELSE 2 END = 1 keeps the discriminant
+        // and compares each branch result.
+        let case = Expr::Case {
+            expr: Some(Box::new(col("host"))),
+            when_then: vec![(lit_str("a"), lit_int(1))],
+            else_expr: Some(Box::new(lit_int(2))),
+        };
+        let expected = Expr::Case {
+            expr: Some(Box::new(col("host"))),
+            when_then: vec![(lit_str("a"), lit_int(1).eq(lit_int(1)))],
+            else_expr: Some(Box::new(lit_int(2).eq(lit_int(1)))),
+        };
+        assert_eq!(rewrite(case.eq(lit_int(1))), expected);
+    }
+
+    #[test]
+    fn fold_case_both_sides() {
+        // Inlining the left CASE leaves `then = CASE ...` branches; the
+        // traversal visits those and inlines the right CASE too, so the
+        // result has literal comparisons at every leaf.
+        let case_left = Expr::Case {
+            expr: None,
+            when_then: vec![(col("a").is_null(), lit_int(1))],
+            else_expr: Some(Box::new(lit_int(2))),
+        };
+        let case_right = Expr::Case {
+            expr: None,
+            when_then: vec![(col("b").is_null(), lit_int(3))],
+            else_expr: Some(Box::new(lit_int(4))),
+        };
+
+        let inner = |then: Expr| Expr::Case {
+            expr: None,
+            when_then: vec![(col("b").is_null(), then.clone().eq(lit_int(3)))],
+            else_expr: Some(Box::new(then.eq(lit_int(4)))),
+        };
+        let expected = Expr::Case {
+            expr: None,
+            when_then: vec![(col("a").is_null(), inner(lit_int(1)))],
+            else_expr: Some(Box::new(inner(lit_int(2)))),
+        };
+        assert_eq!(rewrite(case_left.eq(case_right)), expected);
+    }
+
+    #[test]
+    fn fold_case_on_right_without_else() {
+        let case = Expr::Case {
+            expr: None,
+            when_then: vec![(col("host").is_null(), lit_str("none"))],
+            else_expr: None,
+        };
+        let expr = binary_expr(lit_str("west"), Operator::Eq, case);
+        let expected = Expr::Case {
+            expr: None,
+            when_then: vec![(
+                col("host").is_null(),
+                binary_expr(lit_str("west"), Operator::Eq, lit_str("none")),
+            )],
+            else_expr: None,
+        };
+        assert_eq!(rewrite(expr), expected);
+    }
+
+    fn cmp(op: Operator) -> Expr {
+        binary_expr(col("usage"), op, lit_int(5))
+    }
+
+    #[test]
+    fn not_null_is_dropped_next_to_a_range_comparison() {
+        for op in [
+            Operator::Eq,
+            Operator::NotEq,
+            Operator::Gt,
+            Operator::GtEq,
+            Operator::Lt,
+            Operator::LtEq,
+        ] {
+            let expr = col("usage").is_not_null().and(cmp(op));
+            assert_eq!(rewrite_predicate(expr), cmp(op));
+
+            // the conjuncts commute
+            let expr = cmp(op).and(col("usage").is_not_null());
+            assert_eq!(rewrite_predicate(expr), cmp(op));
+        }
+    }
+
+    #[test]
+    fn negated_is_null_form_is_also_dropped() {
+        let expr = col("usage").is_null().not().and(cmp(Operator::Eq));
+        assert_eq!(rewrite_predicate(expr), cmp(Operator::Eq));
+    }
+
+    #[test]
+    fn non_comparison_op_keeps_the_null_check() {
+        // `usage + 5` is not null-rejecting the way a comparison is: the
+        // null-propagation proof does not apply, so nothing may fire.
+        for op in [Operator::Plus, Operator::Multiply, Operator::And] {
+            let expr = col("usage").is_not_null().and(cmp(op));
+            assert_eq!(rewrite_predicate(expr.clone()), expr);
+        }
+    }
+
+    #[test]
+    fn different_column_keeps_the_null_check() {
+        let expr = col("host").is_not_null().and(cmp(Operator::Gt));
+        assert_eq!(rewrite_predicate(expr.clone()), expr);
+    }
+
+    #[test]
+    fn comparison_is_absorbed_into_not_null_under_or() {
+        for op in [Operator::Eq, Operator::NotEq, Operator::Gt] {
+            let expr = col("usage").is_not_null().or(cmp(op));
+            assert_eq!(rewrite_predicate(expr), col("usage").is_not_null());
+
+            // the disjuncts commute
+            let expr = cmp(op).or(col("usage").is_not_null());
+            assert_eq!(rewrite_predicate(expr), col("usage").is_not_null());
+        }
+    }
+
+    #[test]
+    fn is_null_or_comparison_is_untouched() {
+        // Selects NULL rows plus matching rows; no single disjunct does.
+        let expr = col("usage").is_null().or(cmp(Operator::NotEq));
+        assert_eq!(rewrite_predicate(expr.clone()), expr);
+    }
+
+    #[test]
+    fn or_over_different_columns_is_untouched() {
+        let expr = col("host").is_not_null().or(cmp(Operator::Eq));
+        assert_eq!(rewrite_predicate(expr.clone()), expr);
+    }
+
+    #[test]
+    fn nested_redundancy_collapses_only_bottom_up() {
+        // NOT(usage IS NULL) AND (NOT(usage IS NULL) AND usage = 5)
+        let not_null = || col("usage").is_null().not();
+        let expr = || not_null().and(not_null().and(cmp(Operator::Eq)));
+
+        // Top-down visits the outer AND first, whose right side is still a
+        // conjunction rather than a comparison, so only the inner AND fires.
+        assert_eq!(
+            rewrite_predicate_with(expr(), RewriteOrder::TopDown),
+            not_null().and(cmp(Operator::Eq))
+        );
+
+        // Bottom-up simplifies the inner AND first, exposing the
+        // comparison to the outer one.
+        assert_eq!(
+            rewrite_predicate_with(expr(), RewriteOrder::BottomUp),
+            cmp(Operator::Eq)
+        );
+    }
+
+    #[test]
+    fn conjunction_under_not_is_left_alone() {
+        // NOT(usage IS NOT NULL AND usage = 5) keeps NULL rows; rewriting
+        // the conjunction underneath to NOT(usage = 5) would drop them.
+        let expr = col("usage").is_not_null().and(cmp(Operator::Eq)).not();
+        assert_eq!(rewrite_predicate(expr.clone()), expr);
+        assert_eq!(
+            rewrite_predicate_with(expr.clone(), RewriteOrder::BottomUp),
+            expr
+        );
+
+        // the same shape nested under a top-level AND is still protected
+        let negated = col("usage").is_not_null().and(cmp(Operator::Eq)).not();
+        let expr = cmp(Operator::Gt).and(negated);
+        assert_eq!(rewrite_predicate(expr.clone()), expr);
+    }
+
+    #[test]
+    fn null_literal_comparison_keeps_the_null_check() {
+        let comparison = binary_expr(
+            col("usage"),
+            Operator::Gt,
+            Expr::Literal(ScalarValue::Int64(None)),
+        );
+        let expr = col("usage").is_not_null().and(comparison);
+        assert_eq!(rewrite_predicate(expr.clone()), expr);
+    }
+}
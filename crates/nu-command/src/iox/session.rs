@@ -0,0 +1,262 @@
+// Session-scoped IOx query settings, kept in the `IOX_SESSION` environment
+// record so `iox-session set` in one statement applies to queries in later
+// statements. Query commands fold these in underneath their own `--set`
+// flags, so an explicit flag always wins.
+
+use nu_engine::{get_full_help, CallExt};
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, Spanned,
+    SyntaxShape, Value,
+};
+
+use super::SessionConfig;
+
+const SESSION_ENV_VAR: &str = "IOX_SESSION";
+
+/// Fold the `IOX_SESSION` environment record into a [`SessionConfig`].
+/// Non-record values are ignored rather than erroring, so a clobbered
+/// environment variable degrades to "no session settings".
+pub fn session_config_from_env(env_value: Option<&Value>) -> SessionConfig {
+    let mut config = SessionConfig::new();
+    if let Some(Value::Record { cols, vals, .. }) = env_value {
+        for (col, val) in cols.iter().zip(vals) {
+            if let Ok(value) = val.as_string() {
+                config.set(col.clone(), value);
+            }
+        }
+    }
+    config
+}
+
+#[derive(Clone)]
+pub struct IoxSession;
+
+impl Command for IoxSession {
+    fn name(&self) -> &str {
+        "iox-session"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("iox-session").category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Manage IOx session settings applied to subsequent queries."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "You must use one of the following subcommands. Using this command as-is will only produce this help message."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        Ok(Value::String {
+            val: get_full_help(
+                &Self.signature(),
+                &Self.examples(),
+                engine_state,
+                stack,
+                self.is_parser_keyword(),
+            ),
+            span: call.head,
+        }
+        .into_pipeline_data())
+    }
+}
+
+#[derive(Clone)]
+pub struct IoxSessionSet;
+
+impl Command for IoxSessionSet {
+    fn name(&self) -> &str {
+        "iox-session set"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("iox-session set")
+            .rest(
+                "settings",
+                SyntaxShape::String,
+                "key=value settings to store for the session",
+            )
+            .category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Store session settings that later iox queries apply automatically."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let entries: Vec<Spanned<String>> = call.rest(engine_state, stack, 0)?;
+        let entries: Vec<(String, Span)> =
+            entries.into_iter().map(|e| (e.item, e.span)).collect();
+        let (new_settings, warnings) = SessionConfig::from_entries(&entries)?;
+        for warning in warnings {
+            eprintln!("{warning}");
+        }
+
+        let mut config = session_config_from_env(
+            stack.get_env_var(engine_state, SESSION_ENV_VAR).as_ref(),
+        );
+        for (key, value) in new_settings.settings() {
+            config.set(key.clone(), value.clone());
+        }
+
+        stack.add_env_var(
+            SESSION_ENV_VAR.to_string(),
+            session_record(&config, call.head),
+        );
+        Ok(PipelineData::empty())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![Example {
+            description: "Set a DataFusion setting for the rest of the session",
+            example: "iox-session set target_partitions=4",
+            result: None,
+        }]
+    }
+}
+
+#[derive(Clone)]
+pub struct IoxSessionShow;
+
+impl Command for IoxSessionShow {
+    fn name(&self) -> &str {
+        "iox-session show"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("iox-session show").category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Show the stored IOx session settings."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let config = session_config_from_env(
+            stack.get_env_var(engine_state, SESSION_ENV_VAR).as_ref(),
+        );
+        Ok(session_record(&config, call.head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![Example {
+            description: "List the settings queries will pick up",
+            example: "iox-session show",
+            result: None,
+        }]
+    }
+}
+
+#[derive(Clone)]
+pub struct IoxSessionClear;
+
+impl Command for IoxSessionClear {
+    fn name(&self) -> &str {
+        "iox-session clear"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("iox-session clear").category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Drop all stored IOx session settings."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        _call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        stack.remove_env_var(engine_state, SESSION_ENV_VAR);
+        Ok(PipelineData::empty())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![Example {
+            description: "Forget all session settings",
+            example: "iox-session clear",
+            result: None,
+        }]
+    }
+}
+
+fn session_record(config: &SessionConfig, span: Span) -> Value {
+    let mut cols = vec![];
+    let mut vals = vec![];
+    for (key, value) in config.settings() {
+        cols.push(key.clone());
+        vals.push(Value::String {
+            val: value.clone(),
+            span,
+        });
+    }
+    Value::Record { cols, vals, span }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(IoxSessionSet {});
+        test_examples(IoxSessionShow {});
+        test_examples(IoxSessionClear {});
+    }
+
+    #[test]
+    fn env_record_round_trips_through_session_config() {
+        let mut config = SessionConfig::new();
+        config.set("target_partitions".into(), "4".into());
+        config.set("batch_size".into(), "1024".into());
+
+        let record = session_record(&config, Span::test_data());
+        let restored = session_config_from_env(Some(&record));
+        assert_eq!(restored, config);
+    }
+
+    #[test]
+    fn non_record_env_value_is_ignored() {
+        let clobbered = Value::test_string("oops");
+        assert!(session_config_from_env(Some(&clobbered)).is_empty());
+        assert!(session_config_from_env(None).is_empty());
+    }
+
+    #[test]
+    fn stored_settings_apply_to_a_later_query() {
+        let mut config = SessionConfig::new();
+        config.set("target_partitions".into(), "4".into());
+        let record = session_record(&config, Span::test_data());
+
+        let request = crate::iox::QueryRequest::new("mydb", "select 1")
+            .with_session_config(session_config_from_env(Some(&record)));
+        assert!(request.ticket().contains("target_partitions"));
+    }
+}
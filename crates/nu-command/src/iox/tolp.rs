@@ -0,0 +1,257 @@
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, SyntaxShape,
+    Value,
+};
+
+use super::lp::{datetime_to_timestamp, line_to_lp, FieldValue, ParsedLine};
+
+#[derive(Clone)]
+pub struct IoxToLp;
+
+impl Command for IoxToLp {
+    fn name(&self) -> &str {
+        "to lp"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("to lp")
+            .required_named(
+                "measurement",
+                SyntaxShape::String,
+                "the measurement every line is written under",
+                Some('m'),
+            )
+            .named(
+                "tags",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "columns to serialize as tags rather than fields",
+                Some('t'),
+            )
+            .named(
+                "time-column",
+                SyntaxShape::String,
+                "column holding the timestamp; if omitted, lines are stamped with the current time",
+                None,
+            )
+            .category(Category::Custom("iox".into()))
+    }
+
+    fn usage(&self) -> &str {
+        "Serialize a table to InfluxDB line protocol."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Columns not named by --tags or --time-column become fields. Field values keep their type: ints get the 'i' suffix, strings are quoted."
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["iox", "line protocol", "influx"]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let head = call.head;
+        let measurement: String = call
+            .get_flag(engine_state, stack, "measurement")?
+            .expect("required named flag");
+        let tags: Vec<String> = call
+            .get_flag(engine_state, stack, "tags")?
+            .unwrap_or_default();
+        let time_column: Option<String> = call.get_flag(engine_state, stack, "time-column")?;
+
+        let lines = input
+            .into_iter()
+            .map(|row| {
+                row_to_line(&measurement, &tags, time_column.as_deref(), &row, head)
+                    .map(|line| line_to_lp(&line))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Value::string(lines.join("\n"), head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Serialize a table, tagging by host",
+                example: "[[host usage time]; [a 0.5 100]] | to lp -m cpu -t [host] --time-column time",
+                result: None,
+            },
+            Example {
+                description: "Lines without a time column are stamped with the current time",
+                example: "[[usage]; [0.5]] | to lp -m cpu",
+                result: None,
+            },
+        ]
+    }
+}
+
+fn row_to_line(
+    measurement: &str,
+    tags: &[String],
+    time_column: Option<&str>,
+    row: &Value,
+    head: Span,
+) -> Result<ParsedLine, ShellError> {
+    let span = row.span().unwrap_or(head);
+    let Value::Record { cols, vals, .. } = row else {
+        return Err(ShellError::TypeMismatch(
+            "each row must be a record".into(),
+            span,
+        ));
+    };
+
+    let mut line = ParsedLine {
+        measurement: measurement.to_string(),
+        tags: vec![],
+        fields: vec![],
+        timestamp: None,
+    };
+
+    for tag in tags {
+        let idx = cols.iter().position(|c| c == tag).ok_or_else(|| {
+            ShellError::GenericError(
+                format!("missing tag column '{tag}'"),
+                "this record has no such column".into(),
+                Some(span),
+                None,
+                Vec::new(),
+            )
+        })?;
+        line.tags.push((tag.clone(), vals[idx].as_string()?));
+    }
+
+    for (col, val) in cols.iter().zip(vals) {
+        if tags.contains(col) || Some(col.as_str()) == time_column {
+            continue;
+        }
+        line.fields.push((col.clone(), field_value(col, val)?));
+    }
+    if line.fields.is_empty() {
+        return Err(ShellError::GenericError(
+            "no field columns left".into(),
+            "every column is claimed by --tags or --time-column".into(),
+            Some(span),
+            None,
+            Vec::new(),
+        ));
+    }
+
+    line.timestamp = Some(match time_column {
+        Some(time) => {
+            let idx = cols.iter().position(|c| c == time).ok_or_else(|| {
+                ShellError::GenericError(
+                    format!("missing time column '{time}'"),
+                    "this record has no such column".into(),
+                    Some(span),
+                    None,
+                    Vec::new(),
+                )
+            })?;
+            timestamp_from_value(time, &vals[idx])?
+        }
+        // only an omitted flag means "stamp with the current time"
+        None => datetime_to_timestamp(&chrono::Utc::now()),
+    });
+
+    Ok(line)
+}
+
+fn field_value(col: &str, value: &Value) -> Result<FieldValue, ShellError> {
+    match value {
+        Value::Int { val, .. } => Ok(FieldValue::Int(*val)),
+        Value::Float { val, .. } => Ok(FieldValue::Float(*val)),
+        Value::Bool { val, .. } => Ok(FieldValue::Bool(*val)),
+        Value::String { val, .. } => Ok(FieldValue::String(val.clone())),
+        other => Err(ShellError::TypeMismatch(
+            format!("field column '{col}' cannot be serialized"),
+            other.span().unwrap_or_else(|_| Span::unknown()),
+        )),
+    }
+}
+
+fn timestamp_from_value(col: &str, value: &Value) -> Result<i64, ShellError> {
+    match value {
+        Value::Int { val, .. } => Ok(*val),
+        Value::Date { val, .. } => Ok(val.timestamp_nanos()),
+        other => Err(ShellError::TypeMismatch(
+            format!("time column '{col}' must hold an int or datetime"),
+            other.span().unwrap_or_else(|_| Span::unknown()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(IoxToLp {})
+    }
+
+    fn row() -> Value {
+        Value::test_record(
+            vec!["host", "usage", "cores", "note", "time"],
+            vec![
+                Value::test_string("a"),
+                Value::test_float(0.5),
+                Value::test_int(4),
+                Value::test_string("ok"),
+                Value::test_int(100),
+            ],
+        )
+    }
+
+    #[test]
+    fn typed_fields_get_their_suffixes() {
+        let line = row_to_line(
+            "cpu",
+            &["host".to_string()],
+            Some("time"),
+            &row(),
+            Span::test_data(),
+        )
+        .unwrap();
+        assert_eq!(
+            line_to_lp(&line),
+            r#"cpu,host=a usage=0.5,cores=4i,note="ok" 100"#
+        );
+    }
+
+    #[test]
+    fn missing_time_column_defaults_to_now() {
+        let line = row_to_line("cpu", &[], None, &row(), Span::test_data()).unwrap();
+        assert!(line.timestamp.is_some());
+    }
+
+    #[test]
+    fn missing_declared_time_column_is_an_error() {
+        // a typo'd --time-column must not silently stamp rows with "now"
+        let err = row_to_line("cpu", &[], Some("tme"), &row(), Span::test_data()).unwrap_err();
+        assert!(err.to_string().contains("tme"));
+    }
+
+    #[test]
+    fn missing_declared_tag_is_an_error() {
+        let err = row_to_line(
+            "cpu",
+            &["region".to_string()],
+            None,
+            &row(),
+            Span::test_data(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("region"));
+    }
+}
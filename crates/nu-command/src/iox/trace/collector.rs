@@ -0,0 +1,368 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::Span;
+
+/// Something that accepts completed spans.
+pub trait TraceCollector: std::fmt::Debug + Send + Sync {
+    fn export(&self, span: Span);
+}
+
+/// What to do when the ring buffer is full and another span arrives.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Evict the oldest buffered span to make room (keep the latest spans).
+    #[default]
+    DropOldest,
+    /// Discard the incoming span (keep the first spans, e.g. first errors).
+    DropNewest,
+}
+
+/// Keeps spans in memory, up to a fixed capacity and (optionally) a total
+/// byte budget measured with [`Span::size_bytes`].
+#[derive(Debug)]
+pub struct RingBufferTraceCollector {
+    buffer: Mutex<VecDeque<Span>>,
+    capacity: AtomicUsize,
+    policy: EvictionPolicy,
+    memory_budget: Option<usize>,
+    dropped: AtomicU64,
+}
+
+impl RingBufferTraceCollector {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_policy(capacity, EvictionPolicy::default())
+    }
+
+    pub fn with_policy(capacity: usize, policy: EvictionPolicy) -> Self {
+        RingBufferTraceCollector {
+            buffer: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity: AtomicUsize::new(capacity),
+            policy,
+            memory_budget: None,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Also evict (oldest first) whenever the buffered spans' combined
+    /// [`Span::size_bytes`] would exceed `bytes`. A single span larger than
+    /// the whole budget is still kept, alone, rather than dropped silently.
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// A snapshot of the currently buffered spans, oldest first.
+    pub fn spans(&self) -> Vec<Span> {
+        self.buffer
+            .lock()
+            .expect("trace buffer poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Drop all buffered spans. Deliberate, so not counted as drops.
+    pub fn clear(&self) {
+        self.buffer.lock().expect("trace buffer poisoned").clear();
+    }
+
+    /// How many spans have been lost to eviction — capacity overflow,
+    /// memory-budget pressure, or a shrinking [`resize`](Self::resize).
+    /// Lets a consumer tell whether the buffered trace is complete.
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Change the capacity in place. Shrinking below the current length
+    /// evicts the oldest spans immediately; those count as drops.
+    pub fn resize(&self, new_capacity: usize) {
+        let mut buffer = self.buffer.lock().expect("trace buffer poisoned");
+        self.capacity.store(new_capacity, Ordering::Relaxed);
+        while buffer.len() > new_capacity {
+            buffer.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl TraceCollector for RingBufferTraceCollector {
+    fn export(&self, span: Span) {
+        let mut buffer = self.buffer.lock().expect("trace buffer poisoned");
+        if buffer.len() >= self.capacity.load(Ordering::Relaxed) {
+            match self.policy {
+                EvictionPolicy::DropOldest => {
+                    buffer.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                EvictionPolicy::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+            }
+        }
+        buffer.push_back(span);
+
+        if let Some(budget) = self.memory_budget {
+            let mut total: usize = buffer.iter().map(Span::size_bytes).sum();
+            while total > budget && buffer.len() > 1 {
+                if let Some(evicted) = buffer.pop_front() {
+                    total -= evicted.size_bytes();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+}
+
+/// Forwards only spans that ran for at least a threshold duration,
+/// dropping the fast ones as noise before they reach the wrapped
+/// collector. Incomplete spans have no duration to judge, so they pass
+/// through rather than vanish.
+#[derive(Debug)]
+pub struct MinDurationCollector {
+    inner: Arc<dyn TraceCollector>,
+    threshold: chrono::Duration,
+}
+
+impl MinDurationCollector {
+    pub fn new(inner: Arc<dyn TraceCollector>, threshold: chrono::Duration) -> Self {
+        MinDurationCollector { inner, threshold }
+    }
+}
+
+impl TraceCollector for MinDurationCollector {
+    fn export(&self, span: Span) {
+        match span.duration() {
+            Some(duration) if duration < self.threshold => {}
+            _ => self.inner.export(span),
+        }
+    }
+}
+
+/// Writes one line per exported span to an arbitrary writer (a file, a
+/// pipe to an agent, ...).
+///
+/// Exporting must never disturb the code being traced, so write failures
+/// are swallowed: they bump a counter, are optionally noted on stderr, and
+/// the exporting span carries on. Callers can poll [`failed_exports`] to
+/// find out tracing has gone dark.
+///
+/// [`failed_exports`]: WriterTraceCollector::failed_exports
+pub struct WriterTraceCollector<W: Write + Send> {
+    writer: Mutex<W>,
+    failed_exports: AtomicU64,
+    log_errors: bool,
+}
+
+impl<W: Write + Send> std::fmt::Debug for WriterTraceCollector<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriterTraceCollector")
+            .field("failed_exports", &self.failed_exports)
+            .field("log_errors", &self.log_errors)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write + Send> WriterTraceCollector<W> {
+    pub fn new(writer: W) -> Self {
+        WriterTraceCollector {
+            writer: Mutex::new(writer),
+            failed_exports: AtomicU64::new(0),
+            log_errors: false,
+        }
+    }
+
+    /// Also report each failed export on stderr.
+    pub fn with_error_logging(mut self) -> Self {
+        self.log_errors = true;
+        self
+    }
+
+    /// How many spans have been lost to write failures.
+    pub fn failed_exports(&self) -> u64 {
+        self.failed_exports.load(Ordering::Relaxed)
+    }
+}
+
+impl<W: Write + Send> TraceCollector for WriterTraceCollector<W> {
+    fn export(&self, span: Span) {
+        let mut writer = self.writer.lock().expect("trace writer poisoned");
+        let result = writeln!(
+            writer,
+            "{} trace={:x} span={:x} status={:?} events={}",
+            span.name,
+            span.ctx.trace_id.0,
+            span.ctx.span_id.0,
+            span.status,
+            span.events.len(),
+        )
+        .and_then(|_| writer.flush());
+
+        if let Err(err) = result {
+            self.failed_exports.fetch_add(1, Ordering::Relaxed);
+            if self.log_errors {
+                eprintln!("failed to export span '{}': {err}", span.name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iox::trace::SpanContext;
+    use std::sync::Arc;
+
+    #[test]
+    fn exported_spans_are_buffered() {
+        let collector = Arc::new(RingBufferTraceCollector::new(5));
+        let mut span = Span::new("op", SpanContext::new(Arc::clone(&collector) as _));
+        span.ok("done");
+        span.export();
+
+        let spans = collector.spans();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "op");
+    }
+
+    fn fill(collector: &Arc<RingBufferTraceCollector>, names: &[&'static str]) {
+        for name in names {
+            Span::new(*name, SpanContext::new(Arc::clone(collector) as _)).export();
+        }
+    }
+
+    #[test]
+    fn overflow_evicts_oldest_by_default() {
+        let collector = Arc::new(RingBufferTraceCollector::new(2));
+        fill(&collector, &["a", "b", "c"]);
+
+        let names: Vec<_> = collector.spans().iter().map(|s| s.name.clone()).collect();
+        assert_eq!(names, ["b", "c"]);
+    }
+
+    #[test]
+    fn drop_newest_keeps_first_spans() {
+        let collector = Arc::new(RingBufferTraceCollector::with_policy(
+            2,
+            EvictionPolicy::DropNewest,
+        ));
+        fill(&collector, &["a", "b", "c"]);
+
+        let names: Vec<_> = collector.spans().iter().map(|s| s.name.clone()).collect();
+        assert_eq!(names, ["a", "b"]);
+    }
+
+    #[test]
+    fn dropped_count_reports_lossy_collection() {
+        let collector = Arc::new(RingBufferTraceCollector::new(2));
+        fill(&collector, &["a", "b"]);
+        assert_eq!(collector.dropped_count(), 0);
+
+        fill(&collector, &["c", "d"]);
+        assert_eq!(collector.dropped_count(), 2);
+
+        // discarded incoming spans count too
+        let collector = Arc::new(RingBufferTraceCollector::with_policy(
+            1,
+            EvictionPolicy::DropNewest,
+        ));
+        fill(&collector, &["a", "b", "c"]);
+        assert_eq!(collector.dropped_count(), 2);
+    }
+
+    #[test]
+    fn resize_shrinks_and_grows_in_place() {
+        let collector = Arc::new(RingBufferTraceCollector::new(4));
+        fill(&collector, &["a", "b", "c", "d"]);
+
+        collector.resize(2);
+        let names: Vec<_> = collector.spans().iter().map(|s| s.name.clone()).collect();
+        assert_eq!(names, ["c", "d"]);
+        assert_eq!(collector.dropped_count(), 2);
+
+        collector.resize(3);
+        fill(&collector, &["e"]);
+        let names: Vec<_> = collector.spans().iter().map(|s| s.name.clone()).collect();
+        assert_eq!(names, ["c", "d", "e"]);
+        assert_eq!(collector.dropped_count(), 2);
+    }
+
+    #[test]
+    fn memory_budget_evicts_oldest_spans() {
+        let collector =
+            Arc::new(RingBufferTraceCollector::new(100).with_memory_budget(2048));
+        for name in ["a", "b", "c"] {
+            let mut span = Span::new(name, SpanContext::new(Arc::clone(&collector) as _));
+            // pad each span well past a third of the budget
+            span.set_metadata("pad", "x".repeat(800));
+            span.export();
+        }
+
+        let names: Vec<_> = collector.spans().iter().map(|s| s.name.clone()).collect();
+        assert!(names.len() < 3, "expected eviction, kept {names:?}");
+        assert_eq!(names.last().map(|n| n.as_ref()), Some("c"));
+    }
+
+    #[test]
+    fn min_duration_collector_filters_fast_spans() {
+        let inner = Arc::new(RingBufferTraceCollector::new(5));
+        let filter = Arc::new(MinDurationCollector::new(
+            Arc::clone(&inner) as _,
+            chrono::Duration::milliseconds(100),
+        ));
+
+        let timed = |name: &'static str, millis: i64| {
+            let mut span = Span::new(name, SpanContext::new(Arc::clone(&filter) as _));
+            let start = chrono::Utc::now();
+            span.start = Some(start);
+            span.end = Some(start + chrono::Duration::milliseconds(millis));
+            span.export();
+        };
+        timed("fast", 1);
+        timed("slow", 500);
+
+        // no endpoints: nothing to judge, forwarded
+        Span::new("incomplete", SpanContext::new(Arc::clone(&filter) as _)).export();
+
+        let names: Vec<_> = inner.spans().iter().map(|s| s.name.clone()).collect();
+        assert_eq!(names, ["slow", "incomplete"]);
+    }
+
+    #[test]
+    fn writer_collector_writes_one_line_per_span() {
+        let collector = Arc::new(WriterTraceCollector::new(Vec::new()));
+        let mut span = Span::new("op", SpanContext::new(Arc::clone(&collector) as _));
+        span.ok("done");
+        span.export();
+
+        assert_eq!(collector.failed_exports(), 0);
+        let written = collector.writer.lock().unwrap().clone();
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.starts_with("op trace="));
+        assert!(written.contains("status=Ok"));
+    }
+
+    struct BrokenWriter;
+
+    impl Write for BrokenWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "disk full"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_failures_are_counted_and_do_not_panic() {
+        let collector = Arc::new(WriterTraceCollector::new(BrokenWriter));
+        for _ in 0..3 {
+            Span::new("op", SpanContext::new(Arc::clone(&collector) as _)).export();
+        }
+        assert_eq!(collector.failed_exports(), 3);
+    }
+}
@@ -0,0 +1,127 @@
+// JSON serialization of spans, used when dumping a trace buffer to a file
+// for a bug report.
+
+use nu_json::Value as Json;
+
+use super::{MetaValue, Span};
+
+/// Render spans as a JSON array string, one object per span.
+pub fn spans_to_json(spans: &[Span]) -> String {
+    let array = Json::Array(spans.iter().map(span_to_json).collect());
+    nu_json::to_string_raw(&array).expect("json serialization of spans cannot fail")
+}
+
+fn span_to_json(span: &Span) -> Json {
+    let mut object = nu_json::Map::new();
+    object.insert("name".into(), Json::String(span.name.to_string()));
+    object.insert(
+        "trace_id".into(),
+        Json::String(format!("{:x}", span.ctx.trace_id.0)),
+    );
+    object.insert(
+        "span_id".into(),
+        Json::String(format!("{:x}", span.ctx.span_id.0)),
+    );
+    if let Some(parent) = span.ctx.parent_span_id {
+        object.insert("parent_span_id".into(), Json::String(format!("{:x}", parent.0)));
+    }
+    object.insert("status".into(), Json::String(format!("{:?}", span.status)));
+    if let Some(start) = span.start {
+        object.insert("start".into(), Json::String(start.to_rfc3339()));
+    }
+    if let Some(end) = span.end {
+        object.insert("end".into(), Json::String(end.to_rfc3339()));
+    }
+
+    if !span.metadata.is_empty() {
+        let mut metadata = nu_json::Map::new();
+        for (key, value) in &span.metadata {
+            metadata.insert(key.to_string(), meta_to_json(value));
+        }
+        object.insert("metadata".into(), Json::Object(metadata));
+    }
+
+    let events: Vec<Json> = span
+        .events
+        .iter()
+        .map(|event| {
+            let mut object = nu_json::Map::new();
+            object.insert("time".into(), Json::String(event.time.to_rfc3339()));
+            object.insert("msg".into(), Json::String(event.msg.to_string()));
+            object
+                .insert("seq".into(), Json::U64(event.seq));
+            if !event.metadata.is_empty() {
+                let mut metadata = nu_json::Map::new();
+                for (key, value) in &event.metadata {
+                    metadata.insert(key.to_string(), meta_to_json(value));
+                }
+                object.insert("metadata".into(), Json::Object(metadata));
+            }
+            Json::Object(object)
+        })
+        .collect();
+    if !events.is_empty() {
+        object.insert("events".into(), Json::Array(events));
+    }
+
+    Json::Object(object)
+}
+
+fn meta_to_json(value: &MetaValue) -> Json {
+    match value {
+        MetaValue::String(s) => Json::String(s.to_string()),
+        MetaValue::Float(f) => Json::F64(*f),
+        MetaValue::Int(i) => Json::I64(*i),
+        MetaValue::Bool(b) => Json::Bool(*b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iox::trace::{RingBufferTraceCollector, SpanStatus};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn exported_spans_can_be_read_back() {
+        let collector = Arc::new(RingBufferTraceCollector::new(5));
+        let mut span = Span::root("query", Arc::clone(&collector) as _);
+        span.set_metadata("db", "company_sensors".to_string());
+        span.event_with("sent", HashMap::from([("rows".into(), MetaValue::from(3usize))]));
+        span.ok("done");
+        span.export();
+
+        let json = spans_to_json(&collector.spans());
+        let parsed: Json = nu_json::from_str(&json).unwrap();
+
+        let Json::Array(spans) = parsed else {
+            panic!("expected a JSON array")
+        };
+        assert_eq!(spans.len(), 1);
+        let exported = &spans[0];
+        assert_eq!(
+            exported.find("name"),
+            Some(&Json::String("query".to_string()))
+        );
+        assert_eq!(
+            exported.find("status"),
+            Some(&Json::String(format!("{:?}", SpanStatus::Ok)))
+        );
+        assert_eq!(
+            exported.find_path(&["metadata", "db"]),
+            Some(&Json::String("company_sensors".to_string()))
+        );
+        let Some(Json::Array(events)) = exported.find("events") else {
+            panic!("expected events")
+        };
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].find("msg"), Some(&Json::String("sent".to_string())));
+        // non-negative integers parse back as U64
+        assert_eq!(
+            events[0].find_path(&["metadata", "rows"]),
+            Some(&Json::U64(3))
+        );
+        assert_eq!(events[1].find("metadata"), None);
+    }
+}
@@ -0,0 +1,89 @@
+// A small tracing facility for the IOx commands, modeled on the collector
+// pipeline IOx itself uses: spans carry timing, status, metadata and events,
+// and are handed to a TraceCollector when exported.
+
+mod collector;
+mod export;
+mod recorder;
+mod span;
+
+pub use collector::*;
+pub use export::*;
+pub use recorder::*;
+pub use span::*;
+
+use std::num::NonZeroU64;
+use std::sync::Arc;
+
+/// The process-wide collector the iox commands report to, and the buffer
+/// `ioxtrace` displays, exports and clears.
+pub fn global_trace_collector() -> &'static Arc<RingBufferTraceCollector> {
+    static COLLECTOR: once_cell::sync::Lazy<Arc<RingBufferTraceCollector>> =
+        once_cell::sync::Lazy::new(|| Arc::new(RingBufferTraceCollector::new(1024)));
+    &COLLECTOR
+}
+
+/// Identifies a trace: every span in one trace shares this id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TraceId(pub NonZeroU64);
+
+/// Identifies a single span within a trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpanId(pub NonZeroU64);
+
+impl TraceId {
+    fn gen() -> Self {
+        TraceId(random_id())
+    }
+}
+
+impl SpanId {
+    fn gen() -> Self {
+        SpanId(random_id())
+    }
+}
+
+fn random_id() -> NonZeroU64 {
+    loop {
+        if let Some(id) = NonZeroU64::new(rand::random::<u64>()) {
+            return id;
+        }
+    }
+}
+
+/// The identity of a span plus where its exported data should go.
+#[derive(Clone, Debug)]
+pub struct SpanContext {
+    pub trace_id: TraceId,
+    pub parent_span_id: Option<SpanId>,
+    pub span_id: SpanId,
+    pub links: Vec<(TraceId, SpanId)>,
+    pub collector: Option<Arc<dyn TraceCollector>>,
+}
+
+impl SpanContext {
+    /// A fresh root context reporting to `collector`.
+    pub fn new(collector: Arc<dyn TraceCollector>) -> Self {
+        SpanContext {
+            trace_id: TraceId::gen(),
+            parent_span_id: None,
+            span_id: SpanId::gen(),
+            links: vec![],
+            collector: Some(collector),
+        }
+    }
+
+    /// A new span in the same trace, parented to this context.
+    pub fn child(&self, name: impl Into<std::borrow::Cow<'static, str>>) -> Span {
+        Span::new(
+            name,
+            SpanContext {
+                trace_id: self.trace_id,
+                parent_span_id: Some(self.span_id),
+                span_id: SpanId::gen(),
+                links: vec![],
+                collector: self.collector.clone(),
+            },
+        )
+    }
+}
@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use super::{Span, SpanStatus, TraceCollector};
+
+/// Owns a [`Span`] for the duration of an operation and guarantees it is
+/// exported, complete with its end time, when the recorder is dropped.
+///
+/// Because the recorder owns the span, whatever terminal status has been
+/// recorded by the time of the drop — including `Cancelled` after an
+/// interrupt — is what the collector sees; drop never resets it.
+#[derive(Debug)]
+pub struct SpanRecorder {
+    span: Option<Span>,
+}
+
+impl SpanRecorder {
+    /// Start recording. `None` makes every operation a no-op, so callers
+    /// don't need to branch on whether tracing is enabled.
+    pub fn new(mut span: Option<Span>) -> Self {
+        if let Some(span) = &mut span {
+            span.start = Some(Utc::now());
+        }
+        SpanRecorder { span }
+    }
+
+    /// Start recording a root span, or nothing when the request was not
+    /// sampled. The unsampled path allocates no span and exports nothing,
+    /// so callers instrument identically either way and pay nothing when
+    /// sampling says no.
+    pub fn new_root_sampled(
+        name: impl Into<Cow<'static, str>>,
+        collector: Arc<dyn TraceCollector>,
+        sampled: bool,
+    ) -> Self {
+        SpanRecorder::new(sampled.then(|| Span::root(name, collector)))
+    }
+
+    /// Record an event on the span, if any.
+    pub fn event(&mut self, msg: impl Into<Cow<'static, str>>) {
+        if let Some(span) = &mut self.span {
+            span.event(msg);
+        }
+    }
+
+    /// Record an event and mark the span successful (per the escalation
+    /// policy on [`Span::ok`]).
+    pub fn ok(&mut self, msg: impl Into<Cow<'static, str>>) {
+        if let Some(span) = &mut self.span {
+            span.ok(msg);
+        }
+    }
+
+    /// Record an event and mark the span failed.
+    pub fn error(&mut self, msg: impl Into<Cow<'static, str>>) {
+        if let Some(span) = &mut self.span {
+            span.error(msg);
+        }
+    }
+
+    /// The operation was interrupted: record an "interrupted" event and mark
+    /// the span cancelled. The status survives until the drop-time export.
+    pub fn cancel(&mut self) {
+        if let Some(span) = &mut self.span {
+            span.cancel("interrupted");
+        }
+    }
+
+    /// Set the span's status unconditionally.
+    pub fn set_status(&mut self, status: SpanStatus) {
+        if let Some(span) = &mut self.span {
+            span.set_status(status);
+        }
+    }
+
+    /// The span being recorded, if any.
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+}
+
+impl Drop for SpanRecorder {
+    fn drop(&mut self) {
+        if let Some(span) = &mut self.span {
+            span.end = Some(Utc::now());
+            span.export();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iox::trace::RingBufferTraceCollector;
+    use std::sync::Arc;
+
+    #[test]
+    fn drop_exports_span_with_timing() {
+        let collector = Arc::new(RingBufferTraceCollector::new(5));
+        {
+            let mut recorder =
+                SpanRecorder::new(Some(Span::root("op", Arc::clone(&collector) as _)));
+            recorder.ok("done");
+        }
+
+        let spans = collector.spans();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].status, SpanStatus::Ok);
+        assert!(spans[0].start.is_some());
+        assert!(spans[0].end.is_some());
+    }
+
+    #[test]
+    fn cancel_then_drop_exports_cancelled_status() {
+        let collector = Arc::new(RingBufferTraceCollector::new(5));
+        {
+            let mut recorder =
+                SpanRecorder::new(Some(Span::root("op", Arc::clone(&collector) as _)));
+            recorder.event("query sent");
+            recorder.cancel();
+        }
+
+        let spans = collector.spans();
+        assert_eq!(spans[0].status, SpanStatus::Cancelled);
+        let msgs: Vec<_> = spans[0].events.iter().map(|e| e.msg.as_ref()).collect();
+        assert_eq!(msgs, ["query sent", "interrupted"]);
+    }
+
+    #[test]
+    fn cancel_does_not_downgrade_a_failure() {
+        let collector = Arc::new(RingBufferTraceCollector::new(5));
+        {
+            let mut recorder =
+                SpanRecorder::new(Some(Span::root("op", Arc::clone(&collector) as _)));
+            recorder.error("connection reset");
+            recorder.cancel();
+        }
+        assert_eq!(collector.spans()[0].status, SpanStatus::Err);
+    }
+
+    #[test]
+    fn set_status_err_overrides_a_prior_ok() {
+        let collector = Arc::new(RingBufferTraceCollector::new(5));
+        {
+            let mut recorder =
+                SpanRecorder::new(Some(Span::root("op", Arc::clone(&collector) as _)));
+            recorder.ok("optimistically done");
+            recorder.set_status(SpanStatus::Err);
+        }
+        assert_eq!(collector.spans()[0].status, SpanStatus::Err);
+    }
+
+    #[test]
+    fn sampled_root_recorder_exports() {
+        let collector = Arc::new(RingBufferTraceCollector::new(5));
+        {
+            let mut recorder =
+                SpanRecorder::new_root_sampled("op", Arc::clone(&collector) as _, true);
+            recorder.ok("done");
+        }
+        assert_eq!(collector.spans().len(), 1);
+    }
+
+    #[test]
+    fn unsampled_root_recorder_exports_nothing() {
+        let collector = Arc::new(RingBufferTraceCollector::new(5));
+        {
+            let mut recorder =
+                SpanRecorder::new_root_sampled("op", Arc::clone(&collector) as _, false);
+            assert!(recorder.span().is_none());
+            recorder.ok("done");
+        }
+        assert!(collector.spans().is_empty());
+    }
+
+    #[test]
+    fn disabled_recorder_is_a_no_op() {
+        let mut recorder = SpanRecorder::new(None);
+        recorder.event("ignored");
+        recorder.cancel();
+        assert!(recorder.span().is_none());
+    }
+}
@@ -0,0 +1,430 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use super::{SpanContext, SpanId, TraceCollector, TraceId};
+
+/// The terminal state of a span.
+///
+/// Status follows an escalation policy: `Err` dominates `Cancelled`, which
+/// dominates `Ok`, which dominates `Unknown`. The conditional setters (`ok`,
+/// `error`) only ever move status up this ladder; `set_status` overrides
+/// unconditionally.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpanStatus {
+    Unknown,
+    Ok,
+    /// The operation was interrupted (for instance by Ctrl-C) before it
+    /// could finish.
+    Cancelled,
+    Err,
+}
+
+/// A value attached to span metadata.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetaValue {
+    String(Cow<'static, str>),
+    Float(f64),
+    Int(i64),
+    Bool(bool),
+}
+
+impl MetaValue {
+    pub fn string(&self) -> Option<&str> {
+        match self {
+            MetaValue::String(s) => Some(s.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub fn int(&self) -> Option<i64> {
+        match self {
+            MetaValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn bool(&self) -> Option<bool> {
+        match self {
+            MetaValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Approximate heap-plus-inline size of this value, for collector
+    /// memory accounting.
+    pub fn size_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + match self {
+                MetaValue::String(s) => s.len(),
+                _ => 0,
+            }
+    }
+}
+
+impl From<&'static str> for MetaValue {
+    fn from(v: &'static str) -> Self {
+        MetaValue::String(Cow::Borrowed(v))
+    }
+}
+
+impl From<String> for MetaValue {
+    fn from(v: String) -> Self {
+        MetaValue::String(Cow::Owned(v))
+    }
+}
+
+impl From<f64> for MetaValue {
+    fn from(v: f64) -> Self {
+        MetaValue::Float(v)
+    }
+}
+
+impl From<i64> for MetaValue {
+    fn from(v: i64) -> Self {
+        MetaValue::Int(v)
+    }
+}
+
+impl From<bool> for MetaValue {
+    fn from(v: bool) -> Self {
+        MetaValue::Bool(v)
+    }
+}
+
+// Counters are usually u64/usize; values beyond i64::MAX saturate rather
+// than wrap, which for trace metadata is the least surprising loss.
+impl From<u64> for MetaValue {
+    fn from(v: u64) -> Self {
+        MetaValue::Int(i64::try_from(v).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<usize> for MetaValue {
+    fn from(v: usize) -> Self {
+        MetaValue::Int(i64::try_from(v).unwrap_or(i64::MAX))
+    }
+}
+
+/// A timestamped note recorded while a span was active.
+///
+/// `seq` increases monotonically in call order. Wall-clock timestamps are
+/// not monotonic, so consumers that need reliable ordering should use `seq`;
+/// export re-asserts this order even if timestamps went backwards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpanEvent {
+    pub time: DateTime<Utc>,
+    pub msg: Cow<'static, str>,
+    pub seq: u64,
+    /// Structured fields attached to the event; empty for plain messages.
+    pub metadata: HashMap<Cow<'static, str>, MetaValue>,
+}
+
+/// One operation in a trace.
+#[derive(Clone, Debug)]
+pub struct Span {
+    pub name: Cow<'static, str>,
+    pub ctx: SpanContext,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub status: SpanStatus,
+    pub metadata: HashMap<Cow<'static, str>, MetaValue>,
+    pub events: Vec<SpanEvent>,
+}
+
+/// Link two sibling spans to each other, so fan-out/fan-in traces can be
+/// followed in either direction.
+pub fn link_bidirectional(a: &mut Span, b: &mut Span) {
+    a.ctx.links.push((b.ctx.trace_id, b.ctx.span_id));
+    b.ctx.links.push((a.ctx.trace_id, a.ctx.span_id));
+}
+
+impl Span {
+    pub fn new(name: impl Into<Cow<'static, str>>, ctx: SpanContext) -> Self {
+        Span {
+            name: name.into(),
+            ctx,
+            start: None,
+            end: None,
+            status: SpanStatus::Unknown,
+            metadata: HashMap::new(),
+            events: vec![],
+        }
+    }
+
+    /// A new root span reporting to `collector`.
+    pub fn root(
+        name: impl Into<Cow<'static, str>>,
+        collector: Arc<dyn TraceCollector>,
+    ) -> Self {
+        Span::new(name, SpanContext::new(collector))
+    }
+
+    /// Record a timestamped event on this span.
+    pub fn event(&mut self, msg: impl Into<Cow<'static, str>>) {
+        self.event_with(msg, HashMap::new());
+    }
+
+    /// Record an event carrying structured fields, e.g. a row count next to
+    /// a "batch flushed" message.
+    pub fn event_with(
+        &mut self,
+        msg: impl Into<Cow<'static, str>>,
+        metadata: HashMap<Cow<'static, str>, MetaValue>,
+    ) {
+        let seq = self.events.len() as u64;
+        self.events.push(SpanEvent {
+            time: Utc::now(),
+            msg: msg.into(),
+            seq,
+            metadata,
+        });
+    }
+
+    /// Record an event and mark the span successful, unless it has already
+    /// been marked failed: `ok` never downgrades an `Err` span.
+    pub fn ok(&mut self, msg: impl Into<Cow<'static, str>>) {
+        self.event(msg);
+        if self.status == SpanStatus::Unknown {
+            self.status = SpanStatus::Ok;
+        }
+    }
+
+    /// Record an event and mark the span failed. A late failure dominates:
+    /// this upgrades an `Ok` span to `Err`.
+    pub fn error(&mut self, msg: impl Into<Cow<'static, str>>) {
+        self.event(msg);
+        self.status = SpanStatus::Err;
+    }
+
+    /// Record an event and mark the span cancelled. Cancellation upgrades
+    /// `Unknown` and `Ok` but never downgrades a span that already failed.
+    pub fn cancel(&mut self, msg: impl Into<Cow<'static, str>>) {
+        self.event(msg);
+        if self.status != SpanStatus::Err {
+            self.status = SpanStatus::Cancelled;
+        }
+    }
+
+    /// Set the status unconditionally, ignoring the escalation policy.
+    pub fn set_status(&mut self, status: SpanStatus) {
+        self.status = status;
+    }
+
+    /// Attach a metadata key/value to this span.
+    pub fn set_metadata(
+        &mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<MetaValue>,
+    ) {
+        self.metadata.insert(key.into(), value.into());
+    }
+
+    /// How long the span ran, once both endpoints are known. Collectors use
+    /// this to filter spans below a latency threshold before exporting.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => Some(end - start),
+            _ => None,
+        }
+    }
+
+    /// Whether both endpoints have been recorded.
+    pub fn is_complete(&self) -> bool {
+        self.start.is_some() && self.end.is_some()
+    }
+
+    /// Link this span to a span in another trace.
+    pub fn link(&mut self, other: &SpanContext) {
+        self.ctx.links.push((other.trace_id, other.span_id));
+    }
+
+    /// The spans this one links to, in the order the links were recorded.
+    pub fn links(&self) -> &[(TraceId, SpanId)] {
+        &self.ctx.links
+    }
+
+    /// An estimate of this span's memory footprint: the struct itself plus
+    /// the name, metadata keys/values and event messages it owns. Good
+    /// enough for a collector to enforce a byte budget; not an exact
+    /// allocator-level measurement.
+    pub fn size_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.name.len()
+            + self
+                .metadata
+                .iter()
+                .map(|(key, value)| key.len() + value.size_bytes())
+                .sum::<usize>()
+            + self
+                .events
+                .iter()
+                .map(|event| {
+                    std::mem::size_of::<SpanEvent>()
+                        + event.msg.len()
+                        + event
+                            .metadata
+                            .iter()
+                            .map(|(key, value)| key.len() + value.size_bytes())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+
+    /// Hand a snapshot of this span to its collector, if any. Events are
+    /// sorted back into call order in case a consumer mutated or merged them.
+    pub fn export(&self) {
+        if let Some(collector) = &self.ctx.collector {
+            let mut span = self.clone();
+            span.events.sort_by_key(|event| event.seq);
+            collector.export(span);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iox::trace::RingBufferTraceCollector;
+
+    fn make_span() -> Span {
+        Span::root("test", Arc::new(RingBufferTraceCollector::new(5)))
+    }
+
+    #[test]
+    fn status_starts_unknown() {
+        assert_eq!(make_span().status, SpanStatus::Unknown);
+    }
+
+    #[test]
+    fn ok_sets_status_when_unknown() {
+        let mut span = make_span();
+        span.ok("done");
+        assert_eq!(span.status, SpanStatus::Ok);
+    }
+
+    #[test]
+    fn error_upgrades_ok_to_err() {
+        let mut span = make_span();
+        span.ok("step one done");
+        span.error("step two failed");
+        assert_eq!(span.status, SpanStatus::Err);
+    }
+
+    #[test]
+    fn ok_does_not_downgrade_err() {
+        let mut span = make_span();
+        span.error("failed");
+        span.ok("cleanup finished");
+        assert_eq!(span.status, SpanStatus::Err);
+    }
+
+    #[test]
+    fn set_status_overrides_unconditionally() {
+        let mut span = make_span();
+        span.error("failed");
+        span.set_status(SpanStatus::Ok);
+        assert_eq!(span.status, SpanStatus::Ok);
+    }
+
+    #[test]
+    fn events_are_recorded_in_call_order() {
+        let mut span = make_span();
+        span.event("first");
+        span.ok("second");
+        let msgs: Vec<_> = span.events.iter().map(|e| e.msg.as_ref()).collect();
+        assert_eq!(msgs, ["first", "second"]);
+        let seqs: Vec<_> = span.events.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, [0, 1]);
+    }
+
+    #[test]
+    fn meta_value_conversions() {
+        assert_eq!(MetaValue::from(true), MetaValue::Bool(true));
+        assert_eq!(MetaValue::from(42u64), MetaValue::Int(42));
+        assert_eq!(MetaValue::from(42usize), MetaValue::Int(42));
+        assert_eq!(MetaValue::from(u64::MAX), MetaValue::Int(i64::MAX));
+    }
+
+    #[test]
+    fn meta_value_accessors() {
+        assert_eq!(MetaValue::Int(7).int(), Some(7));
+        assert_eq!(MetaValue::Bool(true).bool(), Some(true));
+        assert_eq!(MetaValue::Int(7).bool(), None);
+        assert_eq!(MetaValue::Bool(true).int(), None);
+        assert_eq!(MetaValue::from("x").int(), None);
+    }
+
+    #[test]
+    fn counting_metadata_needs_no_casts() {
+        let mut span = make_span();
+        let rows: usize = 1024;
+        span.set_metadata("rows", rows);
+        span.set_metadata("partial", false);
+        assert_eq!(span.metadata["rows"].int(), Some(1024));
+        assert_eq!(span.metadata["partial"].bool(), Some(false));
+    }
+
+    #[test]
+    fn duration_requires_both_endpoints() {
+        let mut span = make_span();
+        assert_eq!(span.duration(), None);
+        assert!(!span.is_complete());
+
+        let start = Utc::now();
+        span.start = Some(start);
+        assert_eq!(span.duration(), None);
+        assert!(!span.is_complete());
+
+        span.start = None;
+        span.end = Some(start);
+        assert_eq!(span.duration(), None);
+
+        span.start = Some(start);
+        span.end = Some(start + chrono::Duration::milliseconds(250));
+        assert_eq!(span.duration(), Some(chrono::Duration::milliseconds(250)));
+        assert!(span.is_complete());
+    }
+
+    #[test]
+    fn size_estimate_grows_with_metadata_and_events() {
+        let mut span = make_span();
+        let empty = span.size_bytes();
+
+        span.set_metadata("db", "company_sensors".to_string());
+        let with_metadata = span.size_bytes();
+        assert!(with_metadata > empty);
+
+        span.event("query sent");
+        assert!(span.size_bytes() > with_metadata);
+    }
+
+    #[test]
+    fn bidirectional_links_are_reciprocal() {
+        let mut a = make_span();
+        let mut b = make_span();
+        link_bidirectional(&mut a, &mut b);
+
+        assert_eq!(a.links(), [(b.ctx.trace_id, b.ctx.span_id)]);
+        assert_eq!(b.links(), [(a.ctx.trace_id, a.ctx.span_id)]);
+    }
+
+    #[test]
+    fn export_orders_events_by_sequence_despite_clock_skew() {
+        let collector = Arc::new(RingBufferTraceCollector::new(1));
+        let mut span = Span::root("skewed", Arc::clone(&collector) as _);
+        span.event("first");
+        span.event("second");
+        // simulate the system clock stepping backwards between the events
+        span.events[1].time = span.events[0].time - chrono::Duration::seconds(1);
+        span.events.swap(0, 1);
+
+        span.export();
+
+        let exported = &collector.spans()[0];
+        let msgs: Vec<_> = exported.events.iter().map(|e| e.msg.as_ref()).collect();
+        assert_eq!(msgs, ["first", "second"]);
+    }
+}
@@ -0,0 +1,789 @@
+use nu_protocol::{ShellError, Span, Value};
+use rand::Rng;
+use std::time::Duration;
+
+/// How the target namespace was addressed on the command line (or via the
+/// `IOX_DBNAME`/`IOX_ORG`/`IOX_BUCKET` environment variables).
+///
+/// IOx addresses data either by a single database name or, v2 style, by an
+/// org/bucket pair that combines into the namespace string `org_bucket`.
+/// Exactly one of the two modes must be used. When neither is given the
+/// `dotfile_dbname` fallback (from [`dotfile_dbname`]) applies, so a
+/// project's `.iox` defaults work without flags.
+pub fn resolve_namespace(
+    dbname: Option<String>,
+    org: Option<String>,
+    bucket: Option<String>,
+    dotfile_dbname: Option<String>,
+    span: Span,
+) -> Result<String, ShellError> {
+    match (dbname, org, bucket) {
+        (Some(db), None, None) => Ok(db),
+        (None, Some(org), Some(bucket)) => Ok(format!("{org}_{bucket}")),
+        (Some(_), Some(_), _) | (Some(_), _, Some(_)) => Err(ShellError::GenericError(
+            "ambiguous namespace".into(),
+            "use either --dbname or --org/--bucket, not both".into(),
+            Some(span),
+            None,
+            Vec::new(),
+        )),
+        (None, Some(_), None) | (None, None, Some(_)) => Err(ShellError::GenericError(
+            "incomplete org/bucket pair".into(),
+            "--org and --bucket must be given together".into(),
+            Some(span),
+            None,
+            Vec::new(),
+        )),
+        (None, None, None) => dotfile_dbname.ok_or_else(|| {
+            ShellError::GenericError(
+                "no namespace given".into(),
+                "pass --dbname, or --org and --bucket".into(),
+                Some(span),
+                Some(
+                    "IOX_DBNAME or IOX_ORG/IOX_BUCKET environment variables, or a .iox file, also work"
+                        .into(),
+                ),
+                Vec::new(),
+            )
+        }),
+    }
+}
+
+/// The database an iox command should target: the `-d`/`--dbname` flag if
+/// given, then the engine's `IOX_DBNAME` environment variable (the engine
+/// env, not the process env, so `let-env` works as expected), then the
+/// dbname from a `.iox` dotfile (looked up via [`dotfile_dbname`]). Every
+/// command resolves the name through here so the no-database error reads
+/// the same everywhere.
+pub fn resolve_dbname(
+    flag: Option<String>,
+    env_dbname: Option<String>,
+    dotfile_dbname: Option<String>,
+    span: Span,
+) -> Result<String, ShellError> {
+    flag.or(env_dbname).or(dotfile_dbname).ok_or_else(|| {
+        ShellError::GenericError(
+            "no database given".into(),
+            "pass -d or set IOX_DBNAME".into(),
+            Some(span),
+            Some("a dbname in a .iox file in the current directory or a parent also works".into()),
+            Vec::new(),
+        )
+    })
+}
+
+/// The dbname fallback a `.iox` dotfile at (or above) `cwd` provides,
+/// plus the malformed-file warning when there is one. This is the value
+/// commands hand to [`resolve_dbname`]/[`resolve_namespace`] so the
+/// precedence chain reads flag > environment > dotfile > default.
+pub fn dotfile_dbname(cwd: &std::path::Path) -> (Option<String>, Option<String>) {
+    let (config, warning) = super::config::load_dotfile(cwd);
+    (config.and_then(|config| config.dbname), warning)
+}
+
+/// Find `wanted` among result columns. An exact match always wins; with
+/// `ignore_case`, a unique case-insensitive match is accepted too (IOx
+/// column casing can be surprising), while several candidates differing
+/// only in case are reported as ambiguous rather than picking one.
+pub fn match_column(
+    columns: &[String],
+    wanted: &str,
+    ignore_case: bool,
+    span: Span,
+) -> Result<String, ShellError> {
+    if let Some(exact) = columns.iter().find(|col| *col == wanted) {
+        return Ok(exact.clone());
+    }
+
+    if ignore_case {
+        let candidates: Vec<&String> = columns
+            .iter()
+            .filter(|col| col.eq_ignore_ascii_case(wanted))
+            .collect();
+        match candidates.as_slice() {
+            [unique] => return Ok((*unique).clone()),
+            [] => {}
+            _ => {
+                return Err(ShellError::GenericError(
+                    format!("column '{wanted}' is ambiguous"),
+                    format!(
+                        "matches {} case-insensitively",
+                        candidates
+                            .iter()
+                            .map(|c| format!("'{c}'"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    Some(span),
+                    None,
+                    Vec::new(),
+                ))
+            }
+        }
+    }
+
+    Err(ShellError::GenericError(
+        format!("unknown column '{wanted}'"),
+        format!("existing columns: {}", columns.join(", ")),
+        Some(span),
+        None,
+        Vec::new(),
+    ))
+}
+
+/// Which endpoint a connection is for; IOx serves queries and writes on
+/// different ports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionRole {
+    Query,
+    Write,
+}
+
+impl ConnectionRole {
+    /// The role-specific environment variable, consulted before `IOX_ADDR`.
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            ConnectionRole::Query => "IOX_QUERY_ADDR",
+            ConnectionRole::Write => "IOX_WRITE_ADDR",
+        }
+    }
+
+    fn default_port(&self) -> u16 {
+        match self {
+            ConnectionRole::Query => 8082,
+            ConnectionRole::Write => 8081,
+        }
+    }
+}
+
+/// The address an iox command should dial for `role`: the role-specific
+/// `IOX_QUERY_ADDR`/`IOX_WRITE_ADDR` wins, then the general `IOX_ADDR`,
+/// then the long-standing localhost default for that role's port. Callers
+/// pass the two environment values they looked up so this stays testable.
+pub fn iox_connection_addr(
+    role: ConnectionRole,
+    role_addr: Option<String>,
+    general_addr: Option<String>,
+) -> String {
+    role_addr
+        .or(general_addr)
+        .unwrap_or_else(|| format!("http://127.0.0.1:{}", role.default_port()))
+}
+
+/// How query results are rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Nushell values, left to the table viewer.
+    #[default]
+    Structured,
+    Json,
+    Csv,
+    Pretty,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "structured" => Some(OutputFormat::Structured),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            "pretty" => Some(OutputFormat::Pretty),
+            _ => None,
+        }
+    }
+}
+
+/// Resolve the output format: an explicit `--format` flag wins, then the
+/// `IOX_OUTPUT_FORMAT` environment variable, then the default. An invalid
+/// flag is an error; an invalid environment value falls back to the default
+/// and is reported as a warning so a typo doesn't silently change behavior.
+pub fn resolve_output_format(
+    flag: Option<(String, Span)>,
+    env_value: Option<String>,
+) -> Result<(OutputFormat, Option<String>), ShellError> {
+    if let Some((value, span)) = flag {
+        return match OutputFormat::parse(&value) {
+            Some(format) => Ok((format, None)),
+            None => Err(ShellError::GenericError(
+                format!("invalid output format '{value}'"),
+                "expected structured, json, csv or pretty".into(),
+                Some(span),
+                None,
+                Vec::new(),
+            )),
+        };
+    }
+
+    if let Some(value) = env_value {
+        return Ok(match OutputFormat::parse(&value) {
+            Some(format) => (format, None),
+            None => (
+                OutputFormat::default(),
+                Some(format!(
+                    "ignoring invalid IOX_OUTPUT_FORMAT '{value}'; using the default"
+                )),
+            ),
+        });
+    }
+
+    Ok((OutputFormat::default(), None))
+}
+
+/// Parse a compound duration string like `1h`, `90s`, `500ms` or `1h30m`.
+/// Shared by the relative time bounds and the timeout/interval flags. An
+/// amount too large for the nanosecond range yields `None` like any other
+/// unparseable input; the chrono constructors would panic on it.
+pub fn duration_from_str(raw: &str) -> Option<chrono::Duration> {
+    let mut total = chrono::Duration::zero();
+    let mut digits = String::new();
+    let mut chars = raw.chars().peekable();
+    let mut seen_segment = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let amount: i64 = digits.parse().ok()?;
+        digits.clear();
+
+        let mut unit = String::from(c);
+        while let Some(next) = chars.peek() {
+            if next.is_ascii_alphabetic() {
+                unit.push(*next);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let nanos_per_unit: i64 = match unit.as_str() {
+            "ns" => 1,
+            "us" => 1_000,
+            "ms" => 1_000_000,
+            "s" | "sec" => 1_000_000_000,
+            "m" | "min" => 60 * 1_000_000_000,
+            "h" | "hr" => 3_600 * 1_000_000_000,
+            "d" => 86_400 * 1_000_000_000,
+            "w" => 7 * 86_400 * 1_000_000_000,
+            _ => return None,
+        };
+        let segment = chrono::Duration::nanoseconds(amount.checked_mul(nanos_per_unit)?);
+        total = total.checked_add(&segment)?;
+        seen_segment = true;
+    }
+
+    if !digits.is_empty() || !seen_segment {
+        return None;
+    }
+    Some(total)
+}
+
+/// Parse a duration flag value: either a nushell duration literal (`30sec`)
+/// or a string like `1h30m`/`500ms`. Negative durations are always
+/// rejected; `allow_zero` decides whether `0sec` is acceptable (a timeout
+/// of zero usually isn't, a jitter of zero is).
+pub fn parse_duration(value: &Value, allow_zero: bool) -> Result<Duration, ShellError> {
+    let span = value.span()?;
+    let nanos = match value {
+        Value::Duration { val, .. } => *val,
+        Value::String { val, .. } => duration_from_str(val)
+            .and_then(|d| d.num_nanoseconds())
+            .ok_or_else(|| {
+                ShellError::GenericError(
+                    format!("unable to parse '{val}' as a duration"),
+                    "expected something like 1h30m, 500ms or 2sec".into(),
+                    Some(span),
+                    None,
+                    Vec::new(),
+                )
+            })?,
+        other => {
+            return Err(ShellError::GenericError(
+                "expected a duration".into(),
+                format!("found {}", other.get_type()),
+                Some(span),
+                None,
+                Vec::new(),
+            ))
+        }
+    };
+
+    if nanos < 0 || (nanos == 0 && !allow_zero) {
+        return Err(ShellError::GenericError(
+            "duration out of range".into(),
+            if nanos < 0 {
+                "must be positive".into()
+            } else {
+                "must be greater than zero".into()
+            },
+            Some(span),
+            None,
+            Vec::new(),
+        ));
+    }
+    Ok(Duration::from_nanos(nanos as u64))
+}
+
+/// Turn an I/O failure into a proper shell error instead of a panic site.
+/// `doing` describes the operation ("reading the line protocol file", ...)
+/// so the error points at what was being attempted, and the span points at
+/// the argument that caused it.
+pub fn io_to_shell(err: std::io::Error, doing: &str, span: Span) -> ShellError {
+    let hint = match err.kind() {
+        std::io::ErrorKind::NotFound => Some("check that the path exists".to_string()),
+        std::io::ErrorKind::PermissionDenied => {
+            Some("check the file's permissions".to_string())
+        }
+        _ => None,
+    };
+    ShellError::GenericError(
+        format!("error {doing}"),
+        err.to_string(),
+        Some(span),
+        hint,
+        Vec::new(),
+    )
+}
+
+/// Render a request for `--dump-request` debugging output: the endpoint,
+/// one header per line, then the request message debug form. Credential
+/// headers have their values redacted so dumps are safe to paste into bug
+/// reports; a bearer scheme is kept so it's still visible how the token
+/// would have been sent.
+pub fn dump_request(endpoint: &str, headers: &[(String, String)], message: &str) -> String {
+    let mut out = format!("endpoint: {endpoint}\n");
+    for (name, value) in headers {
+        out.push_str(&format!("header: {name}: {}\n", redact_header(name, value)));
+    }
+    out.push_str(&format!("request: {message}\n"));
+    out
+}
+
+fn redact_header(name: &str, value: &str) -> String {
+    if !matches!(
+        name.to_ascii_lowercase().as_str(),
+        "authorization" | "iox-auth-token"
+    ) {
+        return value.to_string();
+    }
+    match value.split_once(' ') {
+        Some((scheme, _)) => format!("{scheme} <redacted>"),
+        None => "<redacted>".to_string(),
+    }
+}
+
+/// Exponential backoff parameters for [`retry`].
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before the given (1-based) retry attempt.
+    fn delay_before(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        if self.jitter {
+            // spread retries over half to full delay to avoid thundering herds
+            exp.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+        } else {
+            exp
+        }
+    }
+}
+
+/// Run `op` until it succeeds, retrying per `policy` as long as
+/// `is_retryable` classifies the error as transient. The last error is
+/// returned once attempts are exhausted; a non-retryable error is returned
+/// immediately. Different commands supply different policies (writes are
+/// usually retried harder than reads).
+pub fn retry<T, E>(
+    policy: &RetryPolicy,
+    is_retryable: impl Fn(&E) -> bool,
+    mut op: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < policy.max_attempts && is_retryable(&err) => {
+                std::thread::sleep(policy.delay_before(attempt));
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn some(s: &str) -> Option<String> {
+        Some(s.to_string())
+    }
+
+    #[test]
+    fn dbname_mode() {
+        let ns = resolve_namespace(some("mydb"), None, None, None, Span::test_data()).unwrap();
+        assert_eq!(ns, "mydb");
+    }
+
+    #[test]
+    fn org_bucket_mode() {
+        let ns =
+            resolve_namespace(None, some("myorg"), some("mybucket"), None, Span::test_data()).unwrap();
+        assert_eq!(ns, "myorg_mybucket");
+    }
+
+    #[test]
+    fn both_modes_is_an_error() {
+        assert!(
+            resolve_namespace(some("mydb"), some("myorg"), some("mybucket"), None, Span::test_data())
+                .is_err()
+        );
+        assert!(resolve_namespace(some("mydb"), some("myorg"), None, None, Span::test_data()).is_err());
+    }
+
+    #[test]
+    fn half_an_org_bucket_pair_is_an_error() {
+        assert!(resolve_namespace(None, some("myorg"), None, None, Span::test_data()).is_err());
+        assert!(resolve_namespace(None, None, some("mybucket"), None, Span::test_data()).is_err());
+    }
+
+    #[test]
+    fn nothing_given_is_an_error() {
+        assert!(resolve_namespace(None, None, None, None, Span::test_data()).is_err());
+    }
+
+    #[test]
+    fn dbname_flag_beats_the_environment() {
+        let db = resolve_dbname(some("flagdb"), some("envdb"), None, Span::test_data()).unwrap();
+        assert_eq!(db, "flagdb");
+        let db = resolve_dbname(None, some("envdb"), None, Span::test_data()).unwrap();
+        assert_eq!(db, "envdb");
+    }
+
+    #[test]
+    fn missing_dbname_says_how_to_provide_one() {
+        let err = resolve_dbname(None, None, None, Span::test_data()).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("no database given"));
+    }
+
+    #[test]
+    fn dotfile_dbname_sits_below_flag_and_environment() {
+        let db = resolve_dbname(None, None, some("dotdb"), Span::test_data()).unwrap();
+        assert_eq!(db, "dotdb");
+        let db = resolve_dbname(None, some("envdb"), some("dotdb"), Span::test_data()).unwrap();
+        assert_eq!(db, "envdb");
+        let db =
+            resolve_dbname(some("flagdb"), some("envdb"), some("dotdb"), Span::test_data())
+                .unwrap();
+        assert_eq!(db, "flagdb");
+    }
+
+    #[test]
+    fn dotfile_dbname_fills_an_unaddressed_namespace() {
+        let ns = resolve_namespace(None, None, None, some("dotdb"), Span::test_data()).unwrap();
+        assert_eq!(ns, "dotdb");
+
+        // explicit addressing still wins, and a half pair is still an error
+        let ns = resolve_namespace(some("mydb"), None, None, some("dotdb"), Span::test_data())
+            .unwrap();
+        assert_eq!(ns, "mydb");
+        assert!(
+            resolve_namespace(None, some("myorg"), None, some("dotdb"), Span::test_data())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn dotfile_dbname_comes_from_the_dotfile() {
+        let root = std::env::temp_dir().join(format!("iox-util-dotfile-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join(".iox"),
+            "endpoint = \"http://localhost:8082\"\ndbname = \"project_db\"\n",
+        )
+        .unwrap();
+
+        let (dbname, warning) = dotfile_dbname(&root);
+        assert_eq!(dbname.as_deref(), Some("project_db"));
+        assert!(warning.is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    fn cols(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn exact_column_match_wins() {
+        let columns = cols(&["Time", "time", "usage"]);
+        let matched = match_column(&columns, "time", true, Span::test_data()).unwrap();
+        assert_eq!(matched, "time");
+    }
+
+    #[test]
+    fn unique_case_insensitive_match_is_accepted() {
+        let columns = cols(&["Time", "usage"]);
+        let matched = match_column(&columns, "time", true, Span::test_data()).unwrap();
+        assert_eq!(matched, "Time");
+
+        // without the flag the lookup stays strict
+        assert!(match_column(&columns, "time", false, Span::test_data()).is_err());
+    }
+
+    #[test]
+    fn ambiguous_case_insensitive_match_is_an_error() {
+        let columns = cols(&["Time", "TIME", "usage"]);
+        let err = match_column(&columns, "time", true, Span::test_data()).unwrap_err();
+        assert!(err.to_string().contains("ambiguous"));
+    }
+
+    #[test]
+    fn connection_addr_defaults_to_localhost_per_role() {
+        assert_eq!(
+            iox_connection_addr(ConnectionRole::Query, None, None),
+            "http://127.0.0.1:8082"
+        );
+        assert_eq!(
+            iox_connection_addr(ConnectionRole::Write, None, None),
+            "http://127.0.0.1:8081"
+        );
+    }
+
+    #[test]
+    fn general_addr_overrides_the_default() {
+        assert_eq!(
+            iox_connection_addr(ConnectionRole::Query, None, some("http://my-host:9000")),
+            "http://my-host:9000"
+        );
+    }
+
+    #[test]
+    fn role_addr_overrides_the_general_one() {
+        assert_eq!(
+            iox_connection_addr(
+                ConnectionRole::Write,
+                some("http://writer:9001"),
+                some("http://my-host:9000"),
+            ),
+            "http://writer:9001"
+        );
+    }
+
+    #[test]
+    fn env_sets_the_default_format() {
+        let (format, warning) =
+            resolve_output_format(None, Some("json".to_string())).unwrap();
+        assert_eq!(format, OutputFormat::Json);
+        assert!(warning.is_none());
+    }
+
+    #[test]
+    fn flag_overrides_env() {
+        let flag = Some(("csv".to_string(), Span::test_data()));
+        let (format, _) = resolve_output_format(flag, Some("json".to_string())).unwrap();
+        assert_eq!(format, OutputFormat::Csv);
+    }
+
+    #[test]
+    fn invalid_env_falls_back_with_warning() {
+        let (format, warning) =
+            resolve_output_format(None, Some("yamll".to_string())).unwrap();
+        assert_eq!(format, OutputFormat::Structured);
+        assert!(warning.unwrap().contains("yamll"));
+    }
+
+    #[test]
+    fn invalid_flag_is_an_error() {
+        let flag = Some(("yamll".to_string(), Span::test_data()));
+        assert!(resolve_output_format(flag, None).is_err());
+    }
+
+    #[test]
+    fn durations_parse_from_strings_and_duration_values() {
+        let value = Value::test_string("1h30m");
+        assert_eq!(
+            parse_duration(&value, false).unwrap(),
+            Duration::from_secs(90 * 60)
+        );
+        assert_eq!(
+            parse_duration(&Value::test_string("500ms"), false).unwrap(),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            parse_duration(&Value::test_string("2sec"), false).unwrap(),
+            Duration::from_secs(2)
+        );
+
+        let literal = Value::Duration {
+            val: 30_000_000_000,
+            span: Span::test_data(),
+        };
+        assert_eq!(parse_duration(&literal, false).unwrap(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn invalid_durations_are_rejected() {
+        assert!(parse_duration(&Value::test_string("soon"), false).is_err());
+        assert!(parse_duration(&Value::test_string("10"), false).is_err());
+        assert!(parse_duration(&Value::test_int(10), false).is_err());
+    }
+
+    #[test]
+    fn overflowing_duration_is_rejected_not_panicking() {
+        // amounts past the i64 nanosecond range must parse to None instead
+        // of hitting the panicking chrono constructors
+        assert!(duration_from_str("100000000000w").is_none());
+        assert!(duration_from_str("9223372036854775807s").is_none());
+        assert!(parse_duration(&Value::test_string("100000000000w"), false).is_err());
+    }
+
+    #[test]
+    fn zero_and_negative_durations_respect_the_flag() {
+        let zero = Value::Duration {
+            val: 0,
+            span: Span::test_data(),
+        };
+        assert!(parse_duration(&zero, false).is_err());
+        assert_eq!(parse_duration(&zero, true).unwrap(), Duration::ZERO);
+
+        let negative = Value::Duration {
+            val: -1,
+            span: Span::test_data(),
+        };
+        assert!(parse_duration(&negative, true).is_err());
+    }
+
+    #[test]
+    fn not_found_io_error_maps_to_shell_error_with_hint() {
+        let err = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        match io_to_shell(err, "reading the line protocol file", Span::test_data()) {
+            ShellError::GenericError(label, msg, span, hint, _) => {
+                assert_eq!(label, "error reading the line protocol file");
+                assert!(msg.contains("no such file"));
+                assert_eq!(span, Some(Span::test_data()));
+                assert!(hint.unwrap().contains("exists"));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn permission_denied_io_error_maps_with_permission_hint() {
+        let err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        match io_to_shell(err, "creating the runtime", Span::test_data()) {
+            ShellError::GenericError(_, msg, _, hint, _) => {
+                assert!(msg.contains("denied"));
+                assert!(hint.unwrap().contains("permissions"));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn dump_includes_db_name_and_redacts_token() {
+        let request = crate::iox::WriteRequest::new("mydb", "cpu usage=1 100");
+        let dump = dump_request(
+            "http://localhost:8080",
+            &[
+                request.headers()[0].clone(),
+                ("authorization".to_string(), "Bearer s3cr3t".to_string()),
+            ],
+            &format!("{request:?}"),
+        );
+
+        assert!(dump.contains("endpoint: http://localhost:8080"));
+        assert!(dump.contains("mydb"));
+        assert!(dump.contains("Bearer <redacted>"));
+        assert!(!dump.contains("s3cr3t"));
+    }
+
+    #[test]
+    fn non_credential_headers_are_not_redacted() {
+        let dump = dump_request(
+            "http://localhost:8080",
+            &[("content-encoding".to_string(), "gzip".to_string())],
+            "WriteRequest",
+        );
+        assert!(dump.contains("content-encoding: gzip"));
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+            jitter: false,
+        }
+    }
+
+    #[test]
+    fn retry_succeeds_after_transient_failures() {
+        let mut failures_left = 3;
+        let result: Result<&str, &str> = retry(
+            &fast_policy(),
+            |_| true,
+            || {
+                if failures_left > 0 {
+                    failures_left -= 1;
+                    Err("transient")
+                } else {
+                    Ok("done")
+                }
+            },
+        );
+        assert_eq!(result, Ok("done"));
+    }
+
+    #[test]
+    fn retry_gives_up_after_max_attempts() {
+        let mut attempts = 0;
+        let result: Result<(), &str> = retry(
+            &fast_policy(),
+            |_| true,
+            || {
+                attempts += 1;
+                Err("still broken")
+            },
+        );
+        assert_eq!(result, Err("still broken"));
+        assert_eq!(attempts, 5);
+    }
+
+    #[test]
+    fn non_retryable_error_returns_immediately() {
+        let mut attempts = 0;
+        let result: Result<(), &str> = retry(
+            &fast_policy(),
+            |err: &&str| *err != "fatal",
+            || {
+                attempts += 1;
+                Err("fatal")
+            },
+        );
+        assert_eq!(result, Err("fatal"));
+        assert_eq!(attempts, 1);
+    }
+}
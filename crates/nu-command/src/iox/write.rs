@@ -0,0 +1,143 @@
+/// The payload of a write: either line protocol text, or pre-encoded bytes
+/// (e.g. externally gzipped line protocol) forwarded as-is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WriteBody {
+    LineProtocol(String),
+    Raw {
+        bytes: Vec<u8>,
+        content_encoding: Option<String>,
+    },
+}
+
+/// An IOx write before it is sent: the target database, the payload, and
+/// any per-request options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WriteRequest {
+    db_name: String,
+    body: WriteBody,
+    idempotency_key: Option<String>,
+}
+
+impl WriteRequest {
+    pub fn new(db_name: impl Into<String>, lp_data: impl Into<String>) -> Self {
+        WriteRequest {
+            db_name: db_name.into(),
+            body: WriteBody::LineProtocol(lp_data.into()),
+            idempotency_key: None,
+        }
+    }
+
+    /// A `--raw-bytes` write: the payload is sent unmodified, with the given
+    /// content encoding declared, bypassing any UTF-8 assumptions.
+    pub fn new_raw(
+        db_name: impl Into<String>,
+        bytes: Vec<u8>,
+        content_encoding: Option<String>,
+    ) -> Self {
+        WriteRequest {
+            db_name: db_name.into(),
+            body: WriteBody::Raw {
+                bytes,
+                content_encoding,
+            },
+            idempotency_key: None,
+        }
+    }
+
+    /// Attach an idempotency key so a retried identical request can be
+    /// deduplicated server-side. Without one, behavior is unchanged.
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    pub fn db_name(&self) -> &str {
+        &self.db_name
+    }
+
+    /// The line-protocol text, when this is not a raw write.
+    pub fn lp_data(&self) -> Option<&str> {
+        match &self.body {
+            WriteBody::LineProtocol(lp) => Some(lp),
+            WriteBody::Raw { .. } => None,
+        }
+    }
+
+    /// The payload exactly as it will be sent on the wire.
+    pub fn body_bytes(&self) -> &[u8] {
+        match &self.body {
+            WriteBody::LineProtocol(lp) => lp.as_bytes(),
+            WriteBody::Raw { bytes, .. } => bytes,
+        }
+    }
+
+    pub fn idempotency_key(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
+
+    /// The metadata headers sent alongside the write payload.
+    pub fn headers(&self) -> Vec<(String, String)> {
+        let mut headers = vec![("iox-namespace".to_string(), self.db_name.clone())];
+        if let WriteBody::Raw {
+            content_encoding: Some(encoding),
+            ..
+        } = &self.body
+        {
+            headers.push(("content-encoding".to_string(), encoding.clone()));
+        }
+        if let Some(key) = &self.idempotency_key {
+            headers.push(("iox-idempotency-key".to_string(), key.clone()));
+        }
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idempotency_key_is_attached_to_the_request() {
+        let request = WriteRequest::new("mydb", "cpu,region=west usage=1 100")
+            .with_idempotency_key("retry-abc123");
+
+        assert_eq!(request.idempotency_key(), Some("retry-abc123"));
+        assert!(request
+            .headers()
+            .contains(&("iox-idempotency-key".to_string(), "retry-abc123".to_string())));
+    }
+
+    #[test]
+    fn no_key_means_no_header() {
+        let request = WriteRequest::new("mydb", "cpu usage=1 100");
+
+        assert_eq!(request.idempotency_key(), None);
+        assert!(!request
+            .headers()
+            .iter()
+            .any(|(name, _)| name == "iox-idempotency-key"));
+    }
+
+    #[test]
+    fn raw_bytes_are_forwarded_unchanged() {
+        // not valid UTF-8: a gzip header
+        let payload = vec![0x1f, 0x8b, 0x08, 0x00, 0xff, 0xfe];
+        let request = WriteRequest::new_raw("mydb", payload.clone(), Some("gzip".into()));
+
+        assert_eq!(request.body_bytes(), payload.as_slice());
+        assert_eq!(request.lp_data(), None);
+        assert!(request
+            .headers()
+            .contains(&("content-encoding".to_string(), "gzip".to_string())));
+    }
+
+    #[test]
+    fn line_protocol_body_has_no_content_encoding() {
+        let request = WriteRequest::new("mydb", "cpu usage=1 100");
+        assert_eq!(request.body_bytes(), b"cpu usage=1 100");
+        assert!(!request
+            .headers()
+            .iter()
+            .any(|(name, _)| name == "content-encoding"));
+    }
+}
@@ -15,6 +15,7 @@ mod formats;
 mod generators;
 mod hash;
 mod input_handler;
+mod iox;
 mod math;
 mod misc;
 mod network;
@@ -45,6 +46,7 @@ pub use filters::*;
 pub use formats::*;
 pub use generators::*;
 pub use hash::*;
+pub use iox::*;
 pub use math::*;
 pub use misc::*;
 pub use network::*;
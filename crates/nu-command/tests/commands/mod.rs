@@ -80,6 +80,7 @@ mod semicolon;
 mod seq;
 mod seq_char;
 mod shells;
+mod shuffle;
 mod skip;
 mod sort;
 mod sort_by;
@@ -10,6 +10,19 @@ fn can_get_reverse_first() {
     assert_eq!(actual.out, "utf16.ini");
 }
 
+#[test]
+fn reverses_a_string_by_graphemes() {
+    let actual = nu!(cwd: ".", pipeline("'hello' | reverse"));
+    assert_eq!(actual.out, "olleh");
+
+    // the combining acute stays attached to its base character
+    let actual = nu!(cwd: ".", pipeline("'ab\u{0301}c' | reverse"));
+    assert_eq!(actual.out, "cb\u{0301}a");
+
+    let actual = nu!(cwd: ".", pipeline("'' | reverse | str length"));
+    assert_eq!(actual.out, "0");
+}
+
 #[test]
 fn fail_on_non_iterator() {
     let actual = nu!(cwd: ".", pipeline("1 | reverse"));
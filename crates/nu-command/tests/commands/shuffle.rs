@@ -0,0 +1,29 @@
+use nu_test_support::{nu, pipeline};
+
+#[test]
+fn same_seed_same_input_gives_same_order() {
+    let run = || {
+        nu!(
+            cwd: ".", pipeline(
+            r#"
+                [1 2 3 4 5 6 7 8 9 10] | shuffle --seed 42 | to nuon
+            "#
+        ))
+    };
+
+    let first = run();
+    let second = run();
+    assert_eq!(first.out, second.out);
+}
+
+#[test]
+fn different_seeds_give_different_orders() {
+    let actual = nu!(
+        cwd: ".", pipeline(
+        r#"
+            (seq 1 100 | shuffle --seed 1 | to nuon) != (seq 1 100 | shuffle --seed 2 | to nuon)
+        "#
+    ));
+
+    assert_eq!(actual.out, "true");
+}
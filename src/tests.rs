@@ -7,6 +7,7 @@ mod test_custom_commands;
 mod test_engine;
 mod test_env;
 mod test_hiding;
+mod test_iox;
 mod test_iteration;
 mod test_known_external;
 mod test_math;
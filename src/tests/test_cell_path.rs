@@ -52,6 +52,20 @@ fn record_with_nested_list_column_failure() -> TestResult {
     fail_test("{foo: [{bar: 'baz'}]}.foo.0.asdf", "")
 }
 
+// Test that a quoted path segment addresses a key that literally contains a
+// dot (as IOx column names can), as opposed to nested traversal
+#[test]
+fn record_with_dotted_key_success() -> TestResult {
+    run_test(r#"{"a.b": 'c'}."a.b" == 'c'"#, "true")
+}
+
+#[test]
+fn dotted_key_is_not_nested_traversal() -> TestResult {
+    // the unquoted path must keep meaning the nested form
+    run_test(r#"{a: {b: 'nested'}, "a.b": 'flat'}.a.b == 'nested'"#, "true")?;
+    run_test(r#"{a: {b: 'nested'}, "a.b": 'flat'}."a.b" == 'flat'"#, "true")
+}
+
 // Tests for lists
 #[test]
 fn list_single_field_success() -> TestResult {
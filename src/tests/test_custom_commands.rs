@@ -38,6 +38,11 @@ fn custom_rest_var() -> TestResult {
     run_test("def foo [...x] { $x.0 + $x.1 }; foo 10 80", "90")
 }
 
+#[test]
+fn custom_rest_var_empty_binds_empty_list() -> TestResult {
+    run_test("def foo [...x] { $x | is-empty }; foo", "true")
+}
+
 #[test]
 fn def_twice_should_fail() -> TestResult {
     fail_test(
@@ -43,6 +43,11 @@ fn in_variable_6() -> TestResult {
     run_test(r#"3 | if $in > 6 { $in - 10 } else { $in * 10 }"#, "30")
 }
 
+#[test]
+fn in_variable_in_subexpression() -> TestResult {
+    run_test(r#"'hello' | ($in | str length)"#, "5")
+}
+
 #[test]
 fn in_and_if_else() -> TestResult {
     run_test(
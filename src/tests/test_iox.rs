@@ -0,0 +1,25 @@
+use crate::tests::{run_test, TestResult};
+
+#[test]
+fn session_set_persists_to_later_statements() -> TestResult {
+    run_test(
+        r#"iox-session set target_partitions=4; iox-session show | get target_partitions"#,
+        "4",
+    )
+}
+
+#[test]
+fn session_set_overrides_earlier_value() -> TestResult {
+    run_test(
+        r#"iox-session set batch_size=512; iox-session set batch_size=1024; iox-session show | get batch_size"#,
+        "1024",
+    )
+}
+
+#[test]
+fn session_clear_forgets_settings() -> TestResult {
+    run_test(
+        r#"iox-session set target_partitions=4; iox-session clear; iox-session show | columns | length"#,
+        "0",
+    )
+}
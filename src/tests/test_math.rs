@@ -15,6 +15,21 @@ fn broken_math() -> TestResult {
     fail_test("3 + ", "incomplete")
 }
 
+#[test]
+fn int_division_by_zero() -> TestResult {
+    fail_test("10 / 0", "division by zero")
+}
+
+#[test]
+fn float_division_by_zero() -> TestResult {
+    fail_test("10.0 / 0.0", "division by zero")
+}
+
+#[test]
+fn modulo_by_zero() -> TestResult {
+    fail_test("10 mod 0", "division by zero")
+}
+
 #[test]
 fn modulo1() -> TestResult {
     run_test("5 mod 2", "1")
@@ -55,6 +70,16 @@ fn or() -> TestResult {
     run_test("true or false", "true")
 }
 
+#[test]
+fn and_short_circuits() -> TestResult {
+    run_test("false and (10 / 0 == 0)", "false")
+}
+
+#[test]
+fn or_short_circuits() -> TestResult {
+    run_test("true or (10 / 0 == 0)", "true")
+}
+
 #[test]
 fn xor_1() -> TestResult {
     run_test("false xor true", "true")
@@ -95,6 +120,16 @@ fn pow() -> TestResult {
     run_test("3 ** 3", "27")
 }
 
+#[test]
+fn pow_float() -> TestResult {
+    run_test("2 ** 2.0", "4")
+}
+
+#[test]
+fn modulo_of_floats() -> TestResult {
+    run_test("10.5 mod 2.5", "0.5")
+}
+
 #[test]
 fn contains() -> TestResult {
     run_test("'testme' =~ 'test'", "true")
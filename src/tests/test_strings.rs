@@ -8,6 +8,16 @@ fn cjk_in_substrings() -> TestResult {
     )
 }
 
+#[test]
+fn string_concat() -> TestResult {
+    run_test(r#"'foo' + 'bar'"#, "foobar")
+}
+
+#[test]
+fn string_plus_int_errors() -> TestResult {
+    fail_test(r#"'foo' + 1"#, "mismatched for operation")
+}
+
 #[test]
 fn string_not_in_string() -> TestResult {
     run_test(r#"'d' not-in 'abc'"#, "true")
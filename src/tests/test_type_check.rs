@@ -15,6 +15,11 @@ fn type_in_list_of_non_this_type() -> TestResult {
     fail_test(r#"'hello' in [41 42 43]"#, "mismatched for operation")
 }
 
+#[test]
+fn type_not_in_list_of_this_type() -> TestResult {
+    run_test(r#"44 not-in [41 42 43]"#, "true")
+}
+
 #[test]
 fn number_int() -> TestResult {
     run_test(r#"def foo [x:number] { $x }; foo 1"#, "1")